@@ -0,0 +1,191 @@
+//! Derive macro for the `Hintable` trait of
+//! [`giveup`](https://docs.rs/giveup).
+//!
+//! Don't depend on this crate directly. Enable the `derive`
+//! feature of `giveup` instead, which re-exports the macro.
+
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+use std::iter::Peekable;
+
+type Tokens = Peekable<proc_macro::token_stream::IntoIter>;
+
+/// Derive `giveup::Hintable` from `#[hint("...")]` and `#[example("...")]`
+/// attributes.
+///
+/// On structs the attributes are put on the type. On enums they are put
+/// on the variants, and variants without attributes have no hint.
+/// Multiple example commands are added using multiple `#[example]`
+/// attributes or by passing several commands to one of them.
+#[proc_macro_derive(Hintable, attributes(hint, example))]
+pub fn derive_hintable(input: TokenStream) -> TokenStream {
+	match expand(input) {
+		Ok(output) => output,
+		Err(msg) => format!("::std::compile_error!({msg:?});").parse().unwrap(),
+	}
+}
+
+// Hint and examples given by the attributes of a type or variant.
+// The string literals are kept as they are written in the source.
+#[derive(Default)]
+struct Attrs {
+	hint: Option<String>,
+	examples: Vec<String>,
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, String> {
+	let mut tokens = input.into_iter().peekable();
+	let attrs = parse_attrs(&mut tokens)?;
+	skip_visibility(&mut tokens);
+
+	let kind = expect_ident(&mut tokens)?;
+	let name = expect_ident(&mut tokens)?;
+	if matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '<') {
+		return Err("`Hintable` can't be derived for generic types".to_owned());
+	}
+
+	let body = match kind.as_str() {
+		"struct" => hint_expr(&attrs),
+		"enum" => {
+			if attrs.hint.is_some() || !attrs.examples.is_empty() {
+				return Err("put `#[hint]` and `#[example]` on the variants of an enum".to_owned());
+			}
+			let variants = match tokens.next() {
+				Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+					parse_variants(group.stream())?
+				},
+				_ => return Err("expected the variants of the enum".to_owned()),
+			};
+			let arms: String = variants.iter()
+				.map(|(variant, attrs)| format!("Self::{variant} {{ .. }} => {},", hint_expr(attrs)))
+				.collect();
+			format!("match *self {{ {arms} }}")
+		},
+		_ => return Err("`Hintable` can only be derived for structs and enums".to_owned()),
+	};
+
+	let output = format!(
+		"impl ::giveup::Hintable for {name} {{
+			fn hint(&self) -> ::std::option::Option<::giveup::Hint> {{
+				{body}
+			}}
+		}}"
+	);
+	output.parse().map_err(|err| format!("failed to generate `Hintable`: {err}"))
+}
+
+// Expression which creates the `Option<Hint>` described by `attrs`.
+fn hint_expr(attrs: &Attrs) -> String {
+	let hint = match (&attrs.hint, attrs.examples.is_empty()) {
+		(None, true) => return "::std::option::Option::None".to_owned(),
+		(Some(hint), _) => format!("::giveup::Hint::new({hint})"),
+		(None, false) => "<::giveup::Hint as ::std::default::Default>::default()".to_owned(),
+	};
+
+	if attrs.examples.is_empty() {
+		format!("::std::option::Option::Some({hint})")
+	} else {
+		let examples = attrs.examples.join(", ");
+		format!("::std::option::Option::Some(::giveup::Example::examples({hint}, &[{examples}]))")
+	}
+}
+
+// Collect the variants of an enum together with their attributes.
+fn parse_variants(stream: TokenStream) -> Result<Vec<(String, Attrs)>, String> {
+	let mut tokens = stream.into_iter().peekable();
+	let mut variants = Vec::new();
+
+	while tokens.peek().is_some() {
+		let attrs = parse_attrs(&mut tokens)?;
+		let variant = expect_ident(&mut tokens)?;
+		// Skip the fields and discriminant up to the next variant.
+		for token in tokens.by_ref() {
+			if matches!(token, TokenTree::Punct(ref punct) if punct.as_char() == ',') {
+				break;
+			}
+		}
+		variants.push((variant, attrs));
+	}
+
+	Ok(variants)
+}
+
+// Parse all outer attributes, keeping only `#[hint]` and `#[example]`.
+fn parse_attrs(tokens: &mut Tokens) -> Result<Attrs, String> {
+	let mut attrs = Attrs::default();
+
+	while matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '#') {
+		tokens.next();
+		let group = match tokens.next() {
+			Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => group,
+			_ => return Err("expected an attribute".to_owned()),
+		};
+
+		let mut inner = group.stream().into_iter();
+		let name = match inner.next() {
+			Some(TokenTree::Ident(ident)) => ident.to_string(),
+			_ => continue,
+		};
+		if name != "hint" && name != "example" {
+			continue;
+		}
+
+		let args = match inner.next() {
+			Some(TokenTree::Group(args)) if args.delimiter() == Delimiter::Parenthesis => args,
+			_ => return Err(format!("expected `#[{name}(\"...\")]`")),
+		};
+		let literals = parse_string_literals(args.stream())
+			.ok_or_else(|| format!("`#[{name}]` only takes string literals"))?;
+
+		if name == "hint" {
+			match literals.as_slice() {
+				[hint] if attrs.hint.is_none() => attrs.hint = Some(hint.clone()),
+				[_] => return Err("only one `#[hint]` is allowed".to_owned()),
+				_ => return Err("`#[hint]` takes exactly one string literal".to_owned()),
+			}
+		} else {
+			attrs.examples.extend(literals);
+		}
+	}
+
+	Ok(attrs)
+}
+
+// Comma-separated string literals, or `None` if there is anything else.
+fn parse_string_literals(stream: TokenStream) -> Option<Vec<String>> {
+	let mut literals = Vec::new();
+	let mut expect_literal = true;
+
+	for token in stream {
+		match token {
+			TokenTree::Literal(literal) if expect_literal => {
+				let literal = literal.to_string();
+				if !literal.starts_with('"') && !literal.starts_with('r') {
+					return None;
+				}
+				literals.push(literal);
+			},
+			TokenTree::Punct(punct) if !expect_literal && punct.as_char() == ',' => {},
+			_ => return None,
+		}
+		expect_literal = !expect_literal;
+	}
+
+	Some(literals)
+}
+
+// Skip `pub`, `pub(crate)` and the like.
+fn skip_visibility(tokens: &mut Tokens) {
+	if matches!(tokens.peek(), Some(TokenTree::Ident(ident)) if ident.to_string() == "pub") {
+		tokens.next();
+		if matches!(tokens.peek(), Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis) {
+			tokens.next();
+		}
+	}
+}
+
+fn expect_ident(tokens: &mut Tokens) -> Result<String, String> {
+	match tokens.next() {
+		Some(TokenTree::Ident(ident)) => Ok(ident.to_string()),
+		_ => Err("expected an identifier".to_owned()),
+	}
+}
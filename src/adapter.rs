@@ -0,0 +1,433 @@
+#[cfg(feature = "anyhow")]
+use crate::config::current_config;
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+use crate::giveup::{automatic_hints, cause_messages, verbose_debug};
+use crate::exit_code_map::error_exit_code;
+use crate::giveup::{error_segments, Giveup, GiveupExitCode, GiveupFormatError};
+use crate::hint::HintedError;
+use crate::library::Report;
+use crate::options::FormatOptions;
+#[cfg(feature = "anyhow")]
+use crate::report::backtrace_string;
+use crate::report::ErrorReport;
+use std::error::Error;
+use std::fmt::Display;
+
+/// Wrapper which formats boxed errors (`Box<dyn Error>`, optionally
+/// `+ Send` and `+ Sync`) and the errors of `anyhow`, `eyre` and `miette`.
+///
+/// These types don't implement [`std::error::Error`], so they aren't
+/// covered by the implementation of [`GiveupFormatError`] for std
+/// errors, and the compiler doesn't allow implementing it for them
+/// directly next to it. Instead, [`GiveupReport`] wraps their errors
+/// in an `ErrorAdapter`.
+///
+/// Wrap errors yourself where a [`GiveupFormatError`] is required,
+/// e.g. for [`retry_or_giveup`](crate::retry_or_giveup).
+/// # Example
+/// ```rust
+/// use std::error::Error;
+/// use giveup::{ErrorAdapter, GiveupFormatError};
+/// let err: Box<dyn Error + Send + Sync> = "Something failed".into();
+/// assert_eq!(ErrorAdapter::new(err).format_err_msg(), "Something failed\n");
+/// ```
+#[derive(Debug)]
+pub struct ErrorAdapter<E>(E);
+
+impl<E> ErrorAdapter<E> {
+	/// Wrap the given error.
+	pub fn new(e: E) -> Self {
+		Self(e)
+	}
+
+	/// Get the wrapped error.
+	pub fn into_inner(self) -> E {
+		self.0
+	}
+}
+
+// Lets `?` wrap errors, e.g. in `main` returning an `Exit`.
+impl<E> From<E> for ErrorAdapter<E> {
+	fn from(e: E) -> Self {
+		Self(e)
+	}
+}
+
+impl<E> AsRef<E> for ErrorAdapter<E> {
+	fn as_ref(&self) -> &E {
+		&self.0
+	}
+}
+
+// Boxed errors are displayed like the error in the box,
+// including all of its sources.
+impl GiveupFormatError for ErrorAdapter<Box<dyn Error>> {
+	fn segments(&self, opts: &FormatOptions) -> ErrorReport {
+		error_segments(&*self.0, opts)
+	}
+}
+
+impl GiveupExitCode for ErrorAdapter<Box<dyn Error>> {
+	fn exit_code(&self) -> i32 {
+		error_exit_code(&*self.0)
+	}
+}
+
+impl GiveupFormatError for ErrorAdapter<Box<dyn Error + Send>> {
+	fn segments(&self, opts: &FormatOptions) -> ErrorReport {
+		error_segments(&*self.0, opts)
+	}
+}
+
+impl GiveupExitCode for ErrorAdapter<Box<dyn Error + Send>> {
+	fn exit_code(&self) -> i32 {
+		error_exit_code(&*self.0)
+	}
+}
+
+impl GiveupFormatError for ErrorAdapter<Box<dyn Error + Send + Sync>> {
+	fn segments(&self, opts: &FormatOptions) -> ErrorReport {
+		error_segments(&*self.0, opts)
+	}
+}
+
+impl GiveupExitCode for ErrorAdapter<Box<dyn Error + Send + Sync>> {
+	fn exit_code(&self) -> i32 {
+		error_exit_code(&*self.0)
+	}
+}
+
+// The errors are displayed the same way they would be if their
+// outer most error was given up on directly.
+#[cfg(feature = "anyhow")]
+impl GiveupFormatError for ErrorAdapter<anyhow::Error> {
+	fn segments(&self, opts: &FormatOptions) -> ErrorReport {
+		use crate::anyhow_hint::HintContext;
+		let err = &self.0;
+
+		// The Debug implementation of an `anyhow::Error` prints
+		// the whole chain, so the outer most error is used instead.
+		let outer_most: &dyn Error = err.as_ref();
+		let primary = if opts.prefer_debug {
+			format!("{outer_most:?}")
+		} else {
+			// The Display implementation of an `anyhow::Error` matches
+			// the one of the outer most contained error.
+			err.to_string()
+		};
+
+		let hints = automatic_hints(outer_most);
+		let mut report = ErrorReport {
+			primary,
+			// `anyhow::Error::chain` is the same as going back
+			// through the outer most error's sources.
+			causes: cause_messages(outer_most),
+			auto_hints: hints.len(),
+			hints,
+			debug: verbose_debug(outer_most, opts),
+			// anyhow captures its own backtrace based on `RUST_BACKTRACE`.
+			backtrace: backtrace_string(err.backtrace())
+				.filter(|_| current_config().backtrace != Some(false)),
+			..Default::default()
+		};
+
+		// Hints can be attached anywhere in the chain.
+		if let Some(context) = err.downcast_ref::<HintContext>() {
+			context.hint.add_to(&mut report);
+		}
+		report
+	}
+}
+
+#[cfg(feature = "anyhow")]
+impl GiveupExitCode for ErrorAdapter<anyhow::Error> {
+	fn exit_code(&self) -> i32 {
+		error_exit_code(self.0.as_ref())
+	}
+}
+
+#[cfg(feature = "eyre")]
+impl GiveupFormatError for ErrorAdapter<eyre::Report> {
+	fn segments(&self, opts: &FormatOptions) -> ErrorReport {
+		let outer_most: &dyn Error = self.0.as_ref();
+		let primary = if opts.prefer_debug {
+			format!("{outer_most:?}")
+		} else {
+			self.0.to_string()
+		};
+
+		let hints = automatic_hints(outer_most);
+		ErrorReport {
+			primary,
+			causes: cause_messages(outer_most),
+			auto_hints: hints.len(),
+			hints,
+			debug: verbose_debug(outer_most, opts),
+			..Default::default()
+		}
+	}
+}
+
+#[cfg(feature = "eyre")]
+impl GiveupExitCode for ErrorAdapter<eyre::Report> {
+	fn exit_code(&self) -> i32 {
+		error_exit_code(self.0.as_ref())
+	}
+}
+
+/// User-geared program termination for `Result`s with boxed errors
+/// and for the `Result`s of `anyhow`, `eyre` and `miette`.
+///
+/// It has the same methods as [`Giveup`], which is only implemented
+/// for errors implementing [`GiveupFormatError`]. Keeping the two apart
+/// means that enabling one of the features doesn't change which `giveup`
+/// is called on any other `Result`, so type inference in crates which
+/// don't use the feature keeps working.
+/// # Example
+/// ```rust
+/// use std::error::Error;
+/// use giveup::{Giveup, GiveupReport};
+/// fn read_config() -> Result<String, Box<dyn Error + Send + Sync>> {
+///     Ok(String::new())
+/// }
+/// let config = read_config()
+///     .hint("Create a configuration file")
+///     .giveup("Failed to read the configuration");
+/// ```
+pub trait GiveupReport<T, E> {
+	/// Same as [`Giveup::giveup`].
+	fn giveup<M>(self, msg: M) -> T
+	where
+		M: Display;
+
+	/// Same as [`Giveup::giveup_with`].
+	fn giveup_with<F, M>(self, msg: F) -> T
+	where
+		F: FnOnce() -> M,
+		M: Display;
+
+	/// Same as [`Giveup::giveup_code`].
+	fn giveup_code<M>(self, msg: M, code: i32) -> T
+	where
+		M: Display;
+
+	/// Same as [`Giveup::report`].
+	fn report<M>(self, msg: M) -> Result<T, String>
+	where
+		M: Display;
+
+	/// Same as [`Giveup::giveup_err`].
+	fn giveup_err<M>(self, msg: M) -> Result<T, Report>
+	where
+		M: Display;
+
+	/// Same as [`Giveup::complain`].
+	fn complain<M>(self, msg: M) -> Option<T>
+	where
+		M: Display;
+
+	/// Same as [`Giveup::warn`].
+	fn warn<M>(self, msg: M) -> Option<T>
+	where
+		M: Display;
+
+	/// Same as [`Giveup::giveup_or`].
+	fn giveup_or<M>(self, default: T, msg: M) -> T
+	where
+		M: Display;
+
+	/// Same as [`Giveup::giveup_or_default`].
+	fn giveup_or_default<M>(self, msg: M) -> T
+	where
+		T: Default,
+		M: Display;
+
+	/// Same as [`Giveup::hint`]. The error is wrapped in an [`ErrorAdapter`].
+	fn hint<S>(self, hint: S) -> Result<T, HintedError<ErrorAdapter<E>>>
+	where
+		S: Display;
+
+	/// Same as [`Giveup::hint_with`]. The error is wrapped in an [`ErrorAdapter`].
+	fn hint_with<F, S>(self, hint: F) -> Result<T, HintedError<ErrorAdapter<E>>>
+	where
+		F: FnOnce() -> S,
+		S: Display;
+
+	/// Same as [`Giveup::hint_unix`]. The error is wrapped in an [`ErrorAdapter`].
+	fn hint_unix<S>(self, hint: S) -> Result<T, HintedError<ErrorAdapter<E>>>
+	where
+		S: Display;
+
+	/// Same as [`Giveup::hint_windows`]. The error is wrapped in an [`ErrorAdapter`].
+	fn hint_windows<S>(self, hint: S) -> Result<T, HintedError<ErrorAdapter<E>>>
+	where
+		S: Display;
+
+	/// Same as [`Giveup::with_example`]. The error is wrapped in an [`ErrorAdapter`].
+	fn with_example<S>(self, example: S) -> Result<T, HintedError<ErrorAdapter<E>>>
+	where
+		S: Display;
+}
+
+impl<T, E> GiveupReport<T, E> for Result<T, E>
+where
+	ErrorAdapter<E>: GiveupFormatError,
+{
+	#[track_caller]
+	fn giveup<M>(self, msg: M) -> T
+	where
+		M: Display,
+	{
+		self.map_err(ErrorAdapter).giveup(msg)
+	}
+
+	#[track_caller]
+	fn giveup_with<F, M>(self, msg: F) -> T
+	where
+		F: FnOnce() -> M,
+		M: Display,
+	{
+		self.map_err(ErrorAdapter).giveup_with(msg)
+	}
+
+	#[track_caller]
+	fn giveup_code<M>(self, msg: M, code: i32) -> T
+	where
+		M: Display,
+	{
+		self.map_err(ErrorAdapter).giveup_code(msg, code)
+	}
+
+	#[track_caller]
+	fn report<M>(self, msg: M) -> Result<T, String>
+	where
+		M: Display,
+	{
+		self.map_err(ErrorAdapter).report(msg)
+	}
+
+	#[track_caller]
+	fn giveup_err<M>(self, msg: M) -> Result<T, Report>
+	where
+		M: Display,
+	{
+		self.map_err(ErrorAdapter).giveup_err(msg)
+	}
+
+	#[track_caller]
+	fn complain<M>(self, msg: M) -> Option<T>
+	where
+		M: Display,
+	{
+		self.map_err(ErrorAdapter).complain(msg)
+	}
+
+	#[track_caller]
+	fn warn<M>(self, msg: M) -> Option<T>
+	where
+		M: Display,
+	{
+		self.map_err(ErrorAdapter).warn(msg)
+	}
+
+	#[track_caller]
+	fn giveup_or<M>(self, default: T, msg: M) -> T
+	where
+		M: Display,
+	{
+		self.map_err(ErrorAdapter).giveup_or(default, msg)
+	}
+
+	#[track_caller]
+	fn giveup_or_default<M>(self, msg: M) -> T
+	where
+		T: Default,
+		M: Display,
+	{
+		self.map_err(ErrorAdapter).giveup_or_default(msg)
+	}
+
+	#[track_caller]
+	fn hint<S>(self, hint: S) -> Result<T, HintedError<ErrorAdapter<E>>>
+	where
+		S: Display,
+	{
+		self.map_err(ErrorAdapter).hint(hint)
+	}
+
+	#[track_caller]
+	fn hint_with<F, S>(self, hint: F) -> Result<T, HintedError<ErrorAdapter<E>>>
+	where
+		F: FnOnce() -> S,
+		S: Display,
+	{
+		self.map_err(ErrorAdapter).hint_with(hint)
+	}
+
+	#[track_caller]
+	fn hint_unix<S>(self, hint: S) -> Result<T, HintedError<ErrorAdapter<E>>>
+	where
+		S: Display,
+	{
+		self.map_err(ErrorAdapter).hint_unix(hint)
+	}
+
+	#[track_caller]
+	fn hint_windows<S>(self, hint: S) -> Result<T, HintedError<ErrorAdapter<E>>>
+	where
+		S: Display,
+	{
+		self.map_err(ErrorAdapter).hint_windows(hint)
+	}
+
+	#[track_caller]
+	fn with_example<S>(self, example: S) -> Result<T, HintedError<ErrorAdapter<E>>>
+	where
+		S: Display,
+	{
+		self.map_err(ErrorAdapter).with_example(example)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fmt;
+
+	#[derive(Debug)]
+	struct OuterErr(std::io::Error);
+
+	impl fmt::Display for OuterErr {
+		fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+			f.write_str("Failed to read the configuration")
+		}
+	}
+
+	impl Error for OuterErr {
+		fn source(&self) -> Option<&(dyn Error + 'static)> {
+			Some(&self.0)
+		}
+	}
+
+	fn outer_err() -> OuterErr {
+		OuterErr(std::io::Error::other("Permission denied"))
+	}
+
+	#[test]
+	fn boxed_errors_are_displayed_with_their_sources() {
+		let opts = FormatOptions::default();
+		let boxed: Box<dyn Error + Send + Sync> = Box::new(outer_err());
+		let report = ErrorAdapter::new(boxed).segments(&opts);
+		assert_eq!(report.primary, "Failed to read the configuration");
+		assert_eq!(report.causes, vec!["Permission denied"]);
+		assert_eq!(report.format_err_msg(&opts), outer_err().format_err_msg());
+	}
+
+	#[test]
+	fn boxed_errors_can_be_hinted() {
+		let boxed: Result<(), Box<dyn Error>> = Err(Box::new(outer_err()));
+		let report = boxed.hint("Check the permissions").unwrap_err().segments(&FormatOptions::default());
+		assert_eq!(report.primary, "Failed to read the configuration");
+		assert_eq!(report.hints, vec!["Check the permissions"]);
+	}
+}
@@ -0,0 +1,80 @@
+use crate::hint::{Hint, HintedError};
+use std::error::Error;
+use std::fmt::{self, Display};
+
+/// Attach hints to `anyhow` errors while they are passed along.
+///
+/// The hint is displayed once the error reaches
+/// [`giveup`](crate::Giveup::giveup), even if more context
+/// has been added in between.
+pub trait AnyhowHint<T> {
+	/// Attach a hint to the error which is displayed when
+	/// giving up because of it.
+	/// # Example
+	/// ```rust
+	/// # cfg_if::cfg_if! {
+	///     # if #[cfg(feature = "anyhow")] {
+	/// use anyhow::Context;
+	/// use giveup::AnyhowHint;
+	///
+	/// fn read_config() -> anyhow::Result<String> {
+	///     std::fs::read_to_string("config-path")
+	///         .context("Failed to read config-path")
+	///         .with_giveup_hint("Create a configuration file")
+	/// }
+	///     # }
+	/// # }
+	/// ```
+	fn with_giveup_hint<S>(self, hint: S) -> anyhow::Result<T>
+	where
+		S: Display;
+}
+
+impl<T> AnyhowHint<T> for anyhow::Result<T> {
+	fn with_giveup_hint<S>(self, hint: S) -> anyhow::Result<T>
+	where
+		S: Display,
+	{
+		self.map_err(|error| anyhow::Error::new(HintContext {
+			hint: Hint::new(hint),
+			error,
+		}))
+	}
+}
+
+/// Convert a `HintedError` into an `anyhow::Error` which
+/// still displays the hint and examples once it reaches
+/// [`giveup`](crate::Giveup::giveup).
+impl<E> From<HintedError<E>> for anyhow::Error
+where
+	E: Error + Send + Sync + 'static,
+{
+	fn from(err: HintedError<E>) -> Self {
+		anyhow::Error::new(HintContext {
+			hint: *err.hint,
+			error: anyhow::Error::new(err.e),
+		})
+	}
+}
+
+/// Layer in an `anyhow` error which carries a hint.
+///
+/// It is transparent: its message and source are those of the
+/// wrapped error, so the displayed chain doesn't change.
+#[derive(Debug)]
+pub(crate) struct HintContext {
+	pub(crate) hint: Hint,
+	error: anyhow::Error,
+}
+
+impl fmt::Display for HintContext {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(&self.error, f)
+	}
+}
+
+impl Error for HintContext {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		self.error.source()
+	}
+}
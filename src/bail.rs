@@ -0,0 +1,137 @@
+use crate::hint::HintedError;
+use crate::report::ExampleCommand;
+use std::error::Error;
+use std::fmt;
+
+/// Error which consists of nothing but a message.
+///
+/// It is created by [`bail!`](crate::bail!) and [`ensure!`](crate::ensure!)
+/// for failures which aren't caused by another error, e.g. invalid input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageError(String);
+
+impl MessageError {
+	/// Create an error with the given message.
+	pub fn new<S>(msg: S) -> Self
+	where
+		S: fmt::Display,
+	{
+		Self(msg.to_string())
+	}
+}
+
+impl fmt::Display for MessageError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+impl Error for MessageError {}
+
+// Used by `bail!` so the macro doesn't depend on private fields.
+#[doc(hidden)]
+#[track_caller]
+pub fn __message_error(
+	msg: String,
+	hint: Option<String>,
+	examples: Vec<String>,
+) -> HintedError<MessageError> {
+	let mut err = HintedError::new(MessageError(msg));
+	err.hint.hint = hint;
+	err.hint.examples = examples.into_iter().map(ExampleCommand::new).collect();
+	err
+}
+
+/// Return early with a [`HintedError`] made from a message.
+///
+/// The error converts into any error type `HintedError` converts
+/// into, e.g. `anyhow::Error`. A hint and examples can be added
+/// after the message, which has to be a string literal in that case.
+/// # Example
+/// ```rust
+/// use giveup::{HintedError, MessageError};
+///
+/// fn parse_port(port: u32) -> Result<u16, HintedError<MessageError>> {
+///     if port > 65535 {
+///         giveup::bail!("Invalid port {port}", hint = "Ports range from 0 to 65535", example = "--port 8080");
+///     }
+///     Ok(port as u16)
+/// }
+/// assert!(parse_port(8080).is_ok());
+/// assert!(parse_port(70000).is_err());
+/// ```
+#[macro_export]
+macro_rules! bail {
+	($msg:literal $(, hint = $hint:expr)? $(, example = $example:expr)* $(,)?) => {{
+		#[allow(unused_mut, unused_assignments)]
+		let mut hint: ::std::option::Option<::std::string::String> = ::std::option::Option::None;
+		$(hint = ::std::option::Option::Some(::std::convert::Into::into($hint));)?
+		let examples: ::std::vec::Vec<::std::string::String> = ::std::vec![
+			$(::std::convert::Into::into($example)),*
+		];
+		let err = $crate::__message_error(::std::format!($msg), hint, examples);
+		return ::std::result::Result::Err(::std::convert::From::from(err));
+	}};
+	($($arg:tt)+) => {
+		return ::std::result::Result::Err(::std::convert::From::from(
+			$crate::__message_error(::std::format!($($arg)+), ::std::option::Option::None, ::std::vec::Vec::new()),
+		))
+	};
+}
+
+/// Return early with a [`HintedError`] if a condition is false.
+///
+/// Everything after the condition is passed to [`bail!`](crate::bail!).
+/// # Example
+/// ```rust
+/// use giveup::{HintedError, MessageError};
+///
+/// fn check_name(name: &str) -> Result<(), HintedError<MessageError>> {
+///     giveup::ensure!(!name.is_empty(), "The name is empty", hint = "Pass a name with --name");
+///     Ok(())
+/// }
+/// assert!(check_name("giveup").is_ok());
+/// assert!(check_name("").is_err());
+/// ```
+#[macro_export]
+macro_rules! ensure {
+	($cond:expr, $($arg:tt)+) => {
+		if !$cond {
+			$crate::bail!($($arg)+);
+		}
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::report::format_segments;
+
+	fn check(value: u32) -> Result<u32, HintedError<MessageError>> {
+		crate::ensure!(value > 0, "The value is zero", hint = "Pass a positive value", example = "--value 1", example = "--value 2");
+		crate::ensure!(value < 10, "The value {value} is {} large", "too");
+		Ok(value)
+	}
+
+	#[test]
+	fn hints_and_examples_are_attached() {
+		let report = format_segments(&check(0).unwrap_err(), "message");
+		assert_eq!(report.primary, "The value is zero");
+		assert_eq!(report.hints, vec!["Pass a positive value"]);
+		let commands: Vec<_> = report.examples.iter().map(|example| example.command.as_str()).collect();
+		assert_eq!(commands, vec!["--value 1", "--value 2"]);
+	}
+
+	#[test]
+	fn messages_are_formatted() {
+		let report = format_segments(&check(42).unwrap_err(), "message");
+		assert_eq!(report.primary, "The value 42 is too large");
+		assert!(report.hints.is_empty());
+		assert!(report.examples.is_empty());
+	}
+
+	#[test]
+	fn nothing_happens_if_the_condition_holds() {
+		assert_eq!(check(5).unwrap(), 5);
+	}
+}
@@ -0,0 +1,204 @@
+use crate::giveup::{with_caller_context, GiveupFormatError};
+use crate::options::format_options;
+use crate::output::exit_gracefully;
+use crate::report::ErrorReport;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// User-geared program termination for batches of `Result`s.
+pub trait GiveupAll<T, E>
+where
+	E: GiveupFormatError,
+{
+	/// Collect all successful values or, if any of the results is
+	/// an error, terminate the program gracefully and display every
+	/// error together with the position of the result which failed.
+	///
+	/// Unlike `collect::<Result<Vec<_>, _>>()`, this doesn't stop at the
+	/// first error, so users see everything that went wrong at once.
+	/// # Example
+	/// ```rust
+	/// use giveup::GiveupAll;
+	/// let numbers: Vec<u8> = ["1", "2", "3"].iter()
+	///     .map(|s| s.parse::<u8>())
+	///     .giveup_all("Invalid numbers");
+	/// assert_eq!(numbers, vec![1, 2, 3]);
+	/// ```
+	fn giveup_all<M>(self, msg: M) -> Vec<T>
+	where
+		M: Display;
+
+	/// Same as [`giveup_all`](GiveupAll::giveup_all), but errors
+	/// with the same message are only displayed once together
+	/// with how often they occurred and the positions of the results
+	/// which failed with them.
+	/// # Example
+	/// ```rust
+	/// use giveup::GiveupAll;
+	/// let numbers: Vec<u8> = ["1", "2", "3"].iter()
+	///     .map(|s| s.parse::<u8>())
+	///     .giveup_all_grouped("Invalid numbers");
+	/// assert_eq!(numbers, vec![1, 2, 3]);
+	/// ```
+	fn giveup_all_grouped<M>(self, msg: M) -> Vec<T>
+	where
+		M: Display;
+}
+
+impl<I, T, E> GiveupAll<T, E> for I
+where
+	I: IntoIterator<Item = Result<T, E>>,
+	E: GiveupFormatError,
+{
+	#[track_caller]
+	fn giveup_all<M>(self, msg: M) -> Vec<T>
+	where
+		M: Display,
+	{
+		collect_all(self, &msg.to_string(), false)
+	}
+
+	#[track_caller]
+	fn giveup_all_grouped<M>(self, msg: M) -> Vec<T>
+	where
+		M: Display,
+	{
+		collect_all(self, &msg.to_string(), true)
+	}
+}
+
+// Collect the values of all results or give up on all errors.
+#[track_caller]
+fn collect_all<I, T, E>(results: I, msg: &str, grouped: bool) -> Vec<T>
+where
+	I: IntoIterator<Item = Result<T, E>>,
+	E: GiveupFormatError,
+{
+	let opts = format_options();
+	let mut oks = Vec::new();
+	let mut groups: Vec<ErrorGroup> = Vec::new();
+	// Index into `groups` for every distinct error message.
+	let mut seen: HashMap<String, usize> = HashMap::new();
+	let mut total = 0;
+
+	for (idx, result) in results.into_iter().enumerate() {
+		total += 1;
+		match result {
+			Ok(t) => oks.push(t),
+			Err(e) => {
+				let report = e.segments(&opts);
+				let err_msg = report.format_err_msg(&opts);
+				match seen.get(&err_msg).filter(|_| grouped) {
+					Some(&group) => groups[group].indices.push(idx),
+					None => {
+						seen.insert(err_msg.clone(), groups.len());
+						groups.push(ErrorGroup {
+							report,
+							indices: vec![idx],
+							code: e.exit_code(),
+						});
+					},
+				}
+			},
+		}
+	}
+
+	match groups.first() {
+		None => oks,
+		Some(first) => {
+			let report = with_caller_context(ErrorReport {
+				message: msg.to_owned(),
+				..report_groups(&groups, total)
+			});
+			exit_gracefully(&report, first.code)
+		},
+	}
+}
+
+// All results in a batch which failed with the same error.
+struct ErrorGroup {
+	// Report of the first error.
+	report: ErrorReport,
+	// Positions of the failed results in the batch.
+	indices: Vec<usize>,
+	// Exit code of the first error.
+	code: i32,
+}
+
+// Report the errors of a batch of `total` results, with
+// an item for each group.
+fn report_groups(groups: &[ErrorGroup], total: usize) -> ErrorReport {
+	let failed: usize = groups.iter().map(|group| group.indices.len()).sum();
+	let items = groups.iter()
+		.map(|group| {
+			let indices: Vec<String> = group.indices.iter().map(usize::to_string).collect();
+			let message = match group.indices.len() {
+				1 => format!("item {}", indices[0]),
+				n => format!("items {} (x{n})", indices.join(", ")),
+			};
+			ErrorReport {
+				message,
+				..group.report.clone()
+			}
+		})
+		.collect();
+	ErrorReport {
+		primary: format!("{failed} of {total} failed"),
+		items,
+		..ErrorReport::default()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::capture;
+
+	const INVALID_DIGIT: &str = "invalid digit found in string";
+	const EMPTY: &str = "cannot parse integer from empty string";
+
+	#[test]
+	fn all_values_are_returned_without_errors() {
+		let numbers = ["1", "2"].iter().map(|s| s.parse::<u8>()).giveup_all_grouped("message");
+		assert_eq!(numbers, vec![1, 2]);
+	}
+
+	#[test]
+	fn every_error_is_listed() {
+		let captured = capture(|| {
+			["x", "1", "y", ""].iter()
+				.map(|s| s.parse::<u8>())
+				.giveup_all("message");
+		});
+		assert_eq!(captured, vec![(
+			format!("message: 3 of 4 failed\n1. item 0: {INVALID_DIGIT}\n2. item 2: {INVALID_DIGIT}\n3. item 3: {EMPTY}\n"),
+			1,
+		)]);
+	}
+
+	#[test]
+	fn duplicate_errors_are_grouped() {
+		let captured = capture(|| {
+			["x", "", "1", "y", "z"].iter()
+				.map(|s| s.parse::<u8>())
+				.giveup_all_grouped("message");
+		});
+		assert_eq!(captured, vec![(
+			format!("message: 4 of 5 failed\n1. items 0, 3, 4 (x3): {INVALID_DIGIT}\n2. item 1: {EMPTY}\n"),
+			1,
+		)]);
+	}
+
+	#[test]
+	fn unique_errors_are_listed_individually() {
+		let captured = capture(|| {
+			["x", ""].iter()
+				.map(|s| s.parse::<u8>())
+				.giveup_all_grouped("message");
+		});
+		assert_eq!(captured, vec![(
+			format!("message: 2 of 2 failed\n1. item 0: {INVALID_DIGIT}\n2. item 1: {EMPTY}\n"),
+			1,
+		)]);
+	}
+}
@@ -0,0 +1,90 @@
+//! Output which is handed to stderr with a single write.
+//!
+//! Instead of `eprint!`, the report is formatted into a buffer which
+//! is allocated up front and then written at once, so it isn't
+//! interleaved with output written by other threads in the meantime.
+//!
+//! This is not async-signal-safe: composing the report allocates and
+//! the configuration is read behind a lock. `giveup_signal_safe`
+//! of the `signal-safe` feature is.
+
+use crate::config::Config;
+use crate::output::write_report;
+use crate::report::ErrorReport;
+use std::io::{self, Write};
+
+// Capacity reserved for the report so that formatting it
+// usually doesn't need to grow the buffer.
+const BUFFER_CAPACITY: usize = 4096;
+
+// Format the output of `giveup` into a preallocated buffer.
+fn output_buffer(report: &ErrorReport, config: &Config, color: bool) -> String {
+	let capacity = BUFFER_CAPACITY.max(report_len(report) * 2 + 64);
+	let mut buffer = String::with_capacity(capacity);
+	// Writing to a `String` never fails.
+	let _ = write_report(&mut buffer, report, config, color);
+	buffer
+}
+
+// Write the output of `giveup` to stderr at once.
+pub(crate) fn write_report_buffered(report: &ErrorReport, config: &Config, color: bool) {
+	// There is nothing left to report a failed write to.
+	let _ = write_buffered(&mut io::stderr(), report, config, color);
+}
+
+// Same as `write_report_buffered`, but the output is written to `out`.
+fn write_buffered<W>(out: &mut W, report: &ErrorReport, config: &Config, color: bool) -> io::Result<()>
+where
+	W: Write,
+{
+	let buffer = output_buffer(report, config, color);
+	out.write_all(buffer.as_bytes())?;
+	out.flush()
+}
+
+// Combined length of all text in the report.
+fn report_len(report: &ErrorReport) -> usize {
+	let lines = report.causes.iter()
+		.chain(&report.hints)
+		.chain(&report.notes)
+		.chain(&report.links);
+	let examples = report.examples.iter()
+		.map(|example| example.command.len() + example.label.as_ref().map_or(0, String::len));
+	report.message.len()
+		+ report.code.as_ref().map_or(0, String::len)
+		+ report.primary.len()
+		+ lines.map(String::len).sum::<usize>()
+		+ examples.sum::<usize>()
+		+ report.items.iter().map(report_len).sum::<usize>()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::current_config;
+	use crate::output::format_report;
+
+	#[test]
+	fn written_output_matches_the_normal_output() {
+		let config = current_config();
+		let report = ErrorReport {
+			primary: "error".to_owned(),
+			causes: vec!["source".to_owned()],
+			..ErrorReport::with_message("message")
+		};
+		for color in [false, true] {
+			for report in [&report, &ErrorReport::with_message("message")] {
+				let mut written = Vec::new();
+				write_buffered(&mut written, report, &config, color).unwrap();
+				assert_eq!(String::from_utf8(written).unwrap(), format_report(report, &config, color));
+			}
+		}
+	}
+
+	#[test]
+	fn buffer_is_preallocated() {
+		let report = ErrorReport::with_message("message");
+		let buffer = output_buffer(&report, &current_config(), false);
+		assert!(buffer.capacity() >= BUFFER_CAPACITY);
+	}
+}
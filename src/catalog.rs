@@ -0,0 +1,99 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{PoisonError, RwLock};
+
+// Explanations of the codes registered by the application.
+static CATALOG: RwLock<BTreeMap<String, Explanation>> = RwLock::new(BTreeMap::new());
+
+/// Long-form explanation of an [error code](crate::ErrorCode).
+///
+/// It's displayed by [`explain`] as the text followed by the URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+	text: String,
+	url: Option<String>,
+}
+
+impl Explanation {
+	/// Create an explanation with the given text.
+	pub fn new<S>(text: S) -> Self
+	where
+		S: fmt::Display,
+	{
+		Self {
+			text: text.to_string(),
+			url: None,
+		}
+	}
+
+	/// Point to further documentation about the error.
+	pub fn url<S>(mut self, url: S) -> Self
+	where
+		S: fmt::Display,
+	{
+		self.url = Some(url.to_string());
+		self
+	}
+}
+
+impl fmt::Display for Explanation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(f, "{}", self.text.trim_end())?;
+		if let Some(url) = &self.url {
+			writeln!(f, "\nSee {url}")?;
+		}
+		Ok(())
+	}
+}
+
+/// Register the explanation of an error code, replacing
+/// the explanation registered for it before.
+/// # Example
+/// ```rust
+/// use giveup::{register_code, Explanation};
+/// register_code("E012", Explanation::new(
+///     "The configuration file doesn't exist.\n\
+///     Run `app init` to create one with the default settings."
+/// ).url("https://example.com/errors/E012"));
+/// ```
+pub fn register_code<C>(code: C, explanation: Explanation)
+where
+	C: fmt::Display,
+{
+	CATALOG.write()
+		.unwrap_or_else(PoisonError::into_inner)
+		.insert(code.to_string(), explanation);
+}
+
+/// Get the registered explanation of `code`, e.g. to wire
+/// it to an `--explain` flag like `rustc --explain`.
+/// # Example
+/// ```rust
+/// use giveup::{explain, register_code, Explanation};
+/// register_code("E012", Explanation::new("The configuration file doesn't exist."));
+/// if let Some(explanation) = explain("E012") {
+///     print!("{explanation}");
+/// }
+/// assert_eq!(explain("E999"), None);
+/// ```
+pub fn explain(code: &str) -> Option<String> {
+	CATALOG.read()
+		.unwrap_or_else(PoisonError::into_inner)
+		.get(code)
+		.map(Explanation::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn registered_codes_are_explained() {
+		register_code("T001", Explanation::new("First\n").url("https://example.com/T001"));
+		assert_eq!(explain("T001").as_deref(), Some("First\n\nSee https://example.com/T001\n"));
+
+		register_code("T001", Explanation::new("Second"));
+		assert_eq!(explain("T001").as_deref(), Some("Second\n"));
+		assert_eq!(explain("T002"), None);
+	}
+}
@@ -0,0 +1,124 @@
+//! Giving up on the errors of clap's argument parser.
+
+use crate::giveup::{GiveupExitCode, GiveupFormatError};
+use crate::options::FormatOptions;
+use crate::report::ErrorReport;
+
+/// A `clap::Error` which is given up on with clap's usage
+/// and suggestions, and with clap's exit code.
+///
+/// The tips clap adds (e.g. about a similar argument) are displayed
+/// as hints and the usage as a note. Hints and examples can be
+/// attached like for any other error, so argument errors and
+/// runtime errors are displayed by the same pipeline.
+///
+/// Requests for the help or the version aren't errors. Wrapping them
+/// prints the help or version the way clap does and exits successfully.
+/// # Example
+/// ```rust
+/// use giveup::{ClapError, Example, Giveup};
+/// let cmd = clap::Command::new("app")
+///     .arg(clap::Arg::new("port").long("port"));
+/// let matches = cmd.try_get_matches_from(["app", "--port", "80"])
+///     .map_err(ClapError::new)
+///     .hint("Pass the port to listen on")
+///     .example("app --port 8080")
+///     .giveup("Invalid arguments");
+/// ```
+#[derive(Debug)]
+pub struct ClapError(clap::Error);
+
+impl ClapError {
+	/// Wrap the given error, or print the help or version
+	/// and exit if that is what was asked for.
+	pub fn new(e: clap::Error) -> Self {
+		if !e.use_stderr() {
+			e.exit();
+		}
+		Self(e)
+	}
+
+	/// Get the wrapped error.
+	pub fn into_inner(self) -> clap::Error {
+		self.0
+	}
+}
+
+impl From<clap::Error> for ClapError {
+	fn from(e: clap::Error) -> Self {
+		Self::new(e)
+	}
+}
+
+impl GiveupFormatError for ClapError {
+	fn segments(&self, _opts: &FormatOptions) -> ErrorReport {
+		let mut report = ErrorReport::default();
+		// clap renders paragraphs: the error itself, its tips,
+		// the usage and where to find more information.
+		let rendered = self.0.render().to_string();
+		for paragraph in rendered.split("\n\n").map(str::trim_end).filter(|p| !p.is_empty()) {
+			if report.primary.is_empty() {
+				report.primary = paragraph.strip_prefix("error: ").unwrap_or(paragraph).to_owned();
+			} else if paragraph.trim_start().starts_with("tip: ") {
+				let tips = paragraph.lines().filter_map(|line| line.trim_start().strip_prefix("tip: "));
+				report.hints.extend(tips.map(capitalize));
+			} else if paragraph.starts_with("For more information") {
+				report.hints.push(paragraph.to_owned());
+			} else {
+				report.notes.push(paragraph.to_owned());
+			}
+		}
+		report
+	}
+}
+
+impl GiveupExitCode for ClapError {
+	fn exit_code(&self) -> i32 {
+		self.0.exit_code()
+	}
+}
+
+fn capitalize(s: &str) -> String {
+	let mut chars = s.chars();
+	chars.next()
+		.map(|first| first.to_uppercase().chain(chars).collect())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::{Arg, ArgAction, Command};
+
+	fn command() -> Command {
+		Command::new("app")
+			.arg(Arg::new("port").long("port").required(true))
+			.arg(Arg::new("verbose").long("verbose").action(ArgAction::SetTrue))
+	}
+
+	#[test]
+	fn tips_and_usage_are_kept() {
+		let err = ClapError::new(command().try_get_matches_from(["app", "--verbos"]).unwrap_err());
+		assert_eq!(err.format_err_msg(), concat!(
+			"unexpected argument '--verbos' found\n",
+			"- A similar argument exists: '--verbose'\n",
+			"- For more information, try '--help'.\n",
+			"note: Usage: app --port <port> --verbose\n",
+		));
+		assert_eq!(err.exit_code(), 2);
+	}
+
+	#[test]
+	fn argument_errors_can_be_hinted() {
+		use crate::{Example, Giveup};
+		let err = command().try_get_matches_from(["app"])
+			.map_err(ClapError::new)
+			.hint("Pass the port to listen on")
+			.example("app --port 8080")
+			.unwrap_err();
+		let report = err.segments(&FormatOptions::default());
+		assert_eq!(report.primary, "the following required arguments were not provided:\n  --port <port>");
+		assert_eq!(report.hints, vec!["For more information, try '--help'.", "Pass the port to listen on"]);
+		assert_eq!(err.exit_code(), 2);
+	}
+}
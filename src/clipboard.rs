@@ -0,0 +1,60 @@
+//! Copying example commands to the clipboard, so users
+//! don't have to retype them.
+
+use crate::config::Config;
+use crate::report::ErrorReport;
+use std::io::{self, IsTerminal};
+
+// The report with a note that the first example command was
+// copied, if copying is enabled and it worked.
+pub(crate) fn with_copied_example(report: &ErrorReport, config: &Config) -> ErrorReport {
+	let mut report = report.clone();
+	let Some(example) = report.examples.first() else {
+		return report;
+	};
+	// Only someone in front of a terminal can paste the command.
+	if !config.copy_example || config.writer.is_some() || !io::stderr().is_terminal() {
+		return report;
+	}
+	let command = example.command.clone();
+	if copy(&command).is_ok() {
+		report.notes.push(copied_note(&command));
+	}
+	report
+}
+
+// On macOS and Windows the system keeps the copied text after the
+// process exits.
+#[cfg(any(target_os = "macos", windows))]
+fn copy(command: &str) -> Result<(), arboard::Error> {
+	arboard::Clipboard::new()?.set_text(command)
+}
+
+// On X11 and Wayland the text is only served by the process which
+// copied it, so it's gone once the program exits. Don't claim it
+// was copied.
+#[cfg(not(any(target_os = "macos", windows)))]
+fn copy(_command: &str) -> Result<(), arboard::Error> {
+	Err(arboard::Error::ClipboardNotSupported)
+}
+
+fn copied_note(command: &str) -> String {
+	format!("`{command}` (copied to clipboard)")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::current_config;
+	use crate::report::ExampleCommand;
+
+	#[test]
+	fn nothing_is_copied_by_default() {
+		let report = ErrorReport {
+			examples: vec![ExampleCommand::new("touch config.toml")],
+			..ErrorReport::with_message("message")
+		};
+		assert!(with_copied_example(&report, &current_config()).notes.is_empty());
+		assert_eq!(copied_note("touch config.toml"), "`touch config.toml` (copied to clipboard)");
+	}
+}
@@ -0,0 +1,255 @@
+use crate::giveup::Giveup;
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fmt::{self, Display};
+use std::io;
+use std::process::{Command, ExitStatus, Output};
+
+/// User-geared handling of external programs.
+///
+/// Implemented for `std::process::Command`.
+pub trait GiveupCommand {
+	/// Run the command to completion, capturing its output. It fails
+	/// if the command can't be started or exits unsuccessfully.
+	///
+	/// The error contains the command line, the exit status and what
+	/// the command wrote to stderr, so hints can be attached to it
+	/// before giving up.
+	/// # Example
+	/// ```rust no_run
+	/// use std::process::Command;
+	/// use giveup::{Giveup, GiveupCommand};
+	/// let output = Command::new("git")
+	///     .args(["clone", "https://example.com/repo.git"])
+	///     .run_checked()
+	///     .hint("Check that the repository exists")
+	///     .giveup("Failed to clone the repository");
+	/// ```
+	fn run_checked(&mut self) -> Result<Output, CommandError>;
+
+	/// Run the command to completion and return its output, or
+	/// terminate the program gracefully if it fails.
+	/// # Example
+	/// ```rust
+	/// use std::process::Command;
+	/// use giveup::GiveupCommand;
+	/// let output = Command::new("cargo")
+	///     .arg("--version")
+	///     .run_or_giveup("Failed to find cargo");
+	/// assert!(output.stdout.starts_with(b"cargo"));
+	/// ```
+	fn run_or_giveup<M>(&mut self, msg: M) -> Output
+	where
+		M: Display;
+}
+
+impl GiveupCommand for Command {
+	fn run_checked(&mut self) -> Result<Output, CommandError> {
+		let command = command_line(self);
+		let output = match self.output() {
+			Ok(output) => output,
+			Err(e) => return Err(CommandError { command, failure: Failure::Spawn(e) }),
+		};
+		if output.status.success() {
+			return Ok(output);
+		}
+		let stderr = String::from_utf8_lossy(&output.stderr).trim_end().to_owned();
+		Err(CommandError {
+			command,
+			failure: Failure::Status {
+				status: output.status,
+				stderr: Stderr(stderr),
+			},
+		})
+	}
+
+	#[track_caller]
+	fn run_or_giveup<M>(&mut self, msg: M) -> Output
+	where
+		M: Display,
+	{
+		self.run_checked().giveup(msg)
+	}
+}
+
+/// Error of a command which couldn't be started or
+/// which exited unsuccessfully.
+///
+/// Its message contains the command line. What the command wrote
+/// to stderr is displayed as the cause of the error.
+#[derive(Debug)]
+pub struct CommandError {
+	command: String,
+	failure: Failure,
+}
+
+#[derive(Debug)]
+enum Failure {
+	Spawn(io::Error),
+	Status {
+		status: ExitStatus,
+		stderr: Stderr,
+	},
+}
+
+impl CommandError {
+	/// The command line, with arguments quoted where a shell would need it.
+	pub fn command(&self) -> &str {
+		&self.command
+	}
+
+	/// The exit status, unless the command couldn't be started.
+	pub fn status(&self) -> Option<ExitStatus> {
+		match &self.failure {
+			Failure::Spawn(_) => None,
+			Failure::Status { status, .. } => Some(*status),
+		}
+	}
+
+	/// What the command wrote to stderr, without trailing whitespace.
+	pub fn stderr(&self) -> &str {
+		match &self.failure {
+			Failure::Spawn(_) => "",
+			Failure::Status { stderr, .. } => &stderr.0,
+		}
+	}
+}
+
+impl fmt::Display for CommandError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match &self.failure {
+			Failure::Spawn(_) => write!(f, "Failed to run `{}`", self.command),
+			Failure::Status { status, .. } => write!(f, "`{}` failed with {status}", self.command),
+		}
+	}
+}
+
+impl Error for CommandError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match &self.failure {
+			Failure::Spawn(e) => Some(e),
+			Failure::Status { stderr, .. } if stderr.0.is_empty() => None,
+			Failure::Status { stderr, .. } => Some(stderr),
+		}
+	}
+}
+
+/// Turn the exit status of a child process into a `Result`,
+/// so it can be given up on.
+///
+/// Implemented for `std::process::ExitStatus`.
+pub trait GiveupStatus {
+	/// `Ok` if the process exited successfully, otherwise an error
+	/// which displays the exit code or the signal the process was
+	/// killed by.
+	/// # Example
+	/// ```rust
+	/// use std::process::Command;
+	/// use giveup::{Giveup, GiveupStatus};
+	/// Command::new("cargo")
+	///     .arg("--version")
+	///     .status()
+	///     .giveup("Failed to start the build")
+	///     .check_success()
+	///     .giveup("Build step failed");
+	/// ```
+	fn check_success(self) -> Result<(), StatusError>;
+}
+
+impl GiveupStatus for ExitStatus {
+	fn check_success(self) -> Result<(), StatusError> {
+		if self.success() {
+			Ok(())
+		} else {
+			Err(StatusError(self))
+		}
+	}
+}
+
+/// Error of a process which exited unsuccessfully.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusError(ExitStatus);
+
+impl StatusError {
+	/// The exit status of the process.
+	pub fn status(&self) -> ExitStatus {
+		self.0
+	}
+}
+
+impl fmt::Display for StatusError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		// e.g. `exit status: 3` or `signal: 9 (SIGKILL)`.
+		write!(f, "process exited unsuccessfully: {}", self.0)
+	}
+}
+
+impl Error for StatusError {}
+
+// Captured stderr of a command, which is the cause of its failure.
+#[derive(Debug)]
+struct Stderr(String);
+
+impl fmt::Display for Stderr {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+impl Error for Stderr {}
+
+// The program and its arguments as they would be typed in a shell.
+fn command_line(cmd: &Command) -> String {
+	std::iter::once(cmd.get_program())
+		.chain(cmd.get_args())
+		.map(quote)
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+// Arguments are quoted unless they consist of characters
+// which shells don't treat specially.
+fn quote(arg: &OsStr) -> String {
+	let arg = arg.to_string_lossy();
+	let plain = |c: char| c.is_alphanumeric() || "-_./=:,@+%".contains(c);
+	if !arg.is_empty() && arg.chars().all(plain) {
+		arg.into_owned()
+	} else {
+		format!("'{}'", arg.replace('\'', "'\\''"))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::giveup::GiveupFormatError;
+
+	#[cfg(unix)]
+	#[test]
+	fn failed_commands_show_their_stderr() {
+		let err = Command::new("sh")
+			.args(["-c", "echo 'no such remote' >&2; exit 3"])
+			.run_checked()
+			.unwrap_err();
+		assert_eq!(err.status().and_then(|status| status.code()), Some(3));
+		assert_eq!(err.format_err_msg(), "`sh -c 'echo '\\''no such remote'\\'' >&2; exit 3'` failed with exit status: 3\nCaused by: no such remote\n");
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn unsuccessful_statuses_are_errors() {
+		let status = Command::new("sh").args(["-c", "exit 0"]).status().unwrap();
+		assert!(status.check_success().is_ok());
+		let status = Command::new("sh").args(["-c", "exit 3"]).status().unwrap();
+		assert_eq!(status.check_success().unwrap_err().format_err_msg(), "process exited unsuccessfully: exit status: 3\n");
+		let status = Command::new("sh").args(["-c", "kill -9 $$"]).status().unwrap();
+		assert_eq!(status.check_success().unwrap_err().to_string(), "process exited unsuccessfully: signal: 9 (SIGKILL)");
+	}
+
+	#[test]
+	fn commands_which_cannot_be_started_fail() {
+		let err = Command::new("giveup-no-such-program").arg("").run_checked().unwrap_err();
+		assert_eq!(err.to_string(), "Failed to run `giveup-no-such-program ''`");
+		assert_eq!(err.status(), None);
+	}
+}
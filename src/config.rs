@@ -0,0 +1,871 @@
+use crate::exit_code_map::ExitCodeMap;
+use crate::formatter::{Formatter, ReportFormatter};
+use crate::options::{FormatOptions, Verbosity, Wrap, DEFAULT_OPTIONS};
+use crate::redact::Redactor;
+use crate::report::ErrorReport;
+use crate::shell::Shell;
+use crate::strings::Strings;
+use crate::theme::Theme;
+use std::backtrace::Backtrace;
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{self, IsTerminal};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, RwLock};
+
+/// Process-wide configuration of `giveup`.
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+	// Options used to format errors.
+	pub(crate) format: FormatOptions,
+	// Force color on or off. `None` lets the environment decide.
+	pub(crate) color: Option<bool>,
+	// Force backtraces on or off. `None` lets `RUST_BACKTRACE` decide.
+	pub(crate) backtrace: Option<bool>,
+	// Exit code used by errors which don't pick their own.
+	pub(crate) exit_code: i32,
+	// How errors are displayed.
+	pub(crate) output: OutputMode,
+	// Where errors are displayed. `None` means stderr.
+	pub(crate) writer: Option<Writer>,
+	// Styles used if the output is colored.
+	pub(crate) theme: Theme,
+	// Layout of human-readable output. `None` means `DefaultFormatter`.
+	pub(crate) formatter: Option<Formatter>,
+	// Emit GitHub Actions annotations. `None` lets `GITHUB_ACTIONS` decide.
+	pub(crate) github_annotations: Option<bool>,
+	// Mirror errors to journald or syslog. `None` does so
+	// for systemd services.
+	pub(crate) system_log: Option<bool>,
+	// Version of the program written to crash reports.
+	// `None` means no crash reports are written.
+	pub(crate) crash_report: Option<Cow<'static, str>>,
+	// Applied to all text before it's displayed.
+	pub(crate) redactor: Option<Redactor>,
+	// Hints replacing the built-in ones for errors which come
+	// down to an `io::Error`. `None` removes the hint.
+	pub(crate) io_hints: Vec<(io::ErrorKind, Option<Cow<'static, str>>)>,
+	// Picks the exit code of std errors. `None` means `exit_code` is used.
+	pub(crate) exit_code_map: Option<ExitCodeMap>,
+	// Offer to run the example command after giving up.
+	pub(crate) interactive: bool,
+	// Shell whose example commands are displayed. `None` detects it.
+	pub(crate) shell: Option<Shell>,
+	// Put the time in front of human-readable reports.
+	pub(crate) timestamps: bool,
+	// How the program ends after giving up.
+	pub(crate) terminator: Terminator,
+	// Copy the example command to the clipboard when giving up.
+	#[cfg(feature = "clipboard")]
+	pub(crate) copy_example: bool,
+}
+
+/// Destination of reports other than stderr.
+#[derive(Clone)]
+pub(crate) struct Writer(Arc<Mutex<dyn io::Write + Send>>);
+
+impl Writer {
+	pub(crate) fn new<W>(writer: W) -> Self
+	where
+		W: io::Write + Send + 'static,
+	{
+		Self(Arc::new(Mutex::new(writer)))
+	}
+
+	/// Hand the locked writer to `write` and flush it right
+	/// away, since the process might exit directly afterwards.
+	pub(crate) fn write_with<F>(&self, write: F)
+	where
+		F: FnOnce(&mut dyn io::Write) -> io::Result<()>,
+	{
+		let mut writer = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+		// There is nowhere left to report a failed write to.
+		let _ = write(&mut *writer);
+		let _ = writer.flush();
+	}
+}
+
+impl fmt::Debug for Writer {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("Writer(..)")
+	}
+}
+
+/// How errors are displayed on stderr.
+///
+/// Users can override it by setting the `GIVEUP_STYLE` environment
+/// variable to `plain` or `fancy` for uncolored or colored human-readable
+/// output, or to `json` for JSON output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputMode {
+	/// Human-readable messages (the default).
+	#[default]
+	Human,
+	/// A single line of JSON per error for machine consumers,
+	/// as produced by [`ErrorReport::to_json`](crate::ErrorReport::to_json).
+	Json,
+}
+
+/// How the program ends after giving up.
+///
+/// Some hosts (e.g. plugins, test harnesses or embedders) must never
+/// call `process::exit`. They can end giving up differently, e.g. by
+/// unwinding back to where they can recover.
+/// # Example
+/// ```rust
+/// use giveup::{ErrorReport, Terminator};
+///
+/// // Unwind to the host instead of exiting the process.
+/// fn unwind(report: &ErrorReport, code: i32) -> ! {
+///     std::panic::resume_unwind(Box::new((report.clone(), code)))
+/// }
+///
+/// giveup::config().terminator(Terminator::Custom(unwind)).apply();
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Terminator {
+	/// Exit the process with the error's exit code (the default).
+	#[default]
+	Exit,
+	/// Abort the process, e.g. so a core dump is written.
+	Abort,
+	/// Pass the displayed report and the error's exit code to a
+	/// function which doesn't return.
+	Custom(fn(&ErrorReport, i32) -> !),
+}
+
+pub(crate) const DEFAULT_CONFIG: Config = Config {
+	format: DEFAULT_OPTIONS,
+	color: None,
+	// Tests compare whole messages, so they can't depend on `RUST_BACKTRACE`.
+	backtrace: if cfg!(test) { Some(false) } else { None },
+	exit_code: 1,
+	output: OutputMode::Human,
+	writer: None,
+	theme: Theme::standard(),
+	formatter: None,
+	github_annotations: None,
+	system_log: None,
+	crash_report: None,
+	redactor: None,
+	io_hints: Vec::new(),
+	exit_code_map: None,
+	interactive: false,
+	shell: None,
+	timestamps: false,
+	terminator: Terminator::Exit,
+	#[cfg(feature = "clipboard")]
+	copy_example: false,
+};
+
+static CONFIG: RwLock<Config> = RwLock::new(DEFAULT_CONFIG);
+
+/// Start configuring `giveup` for the whole process.
+///
+/// The returned builder starts out with the current configuration.
+/// Nothing changes until [`apply`](ConfigBuilder::apply) is called.
+/// # Example
+/// ```rust
+/// use giveup::Verbosity;
+/// giveup::config()
+///     .app_name("myapp")
+///     .error_label(true)
+///     .color(false)
+///     .exit_code(2)
+///     .verbosity(Verbosity::Quiet)
+///     .apply();
+/// ```
+pub fn config() -> ConfigBuilder {
+	ConfigBuilder {
+		config: current_config(),
+	}
+}
+
+/// Builder for the process-wide configuration returned by [`config`].
+#[derive(Debug, Clone)]
+#[must_use = "the configuration only takes effect once `apply` is called"]
+pub struct ConfigBuilder {
+	config: Config,
+}
+
+impl ConfigBuilder {
+	/// Turn styled output on or off regardless of the environment.
+	///
+	/// Without the `color` feature the output is never styled.
+	pub fn color(mut self, color: bool) -> Self {
+		self.config.color = Some(color);
+		self
+	}
+
+	/// Set the styles used if the output is colored.
+	/// Defaults to [`Theme::standard`].
+	pub fn theme(mut self, theme: Theme) -> Self {
+		self.config.theme = theme;
+		self
+	}
+
+	/// Always or never capture backtraces, regardless of
+	/// `RUST_BACKTRACE` and `RUST_LIB_BACKTRACE`.
+	pub fn backtrace(mut self, backtrace: bool) -> Self {
+		self.config.backtrace = Some(backtrace);
+		self
+	}
+
+	/// Set the code to exit with if the error doesn't pick its own
+	/// [`exit_code`](crate::GiveupExitCode::exit_code). Defaults to 1.
+	pub fn exit_code(mut self, code: i32) -> Self {
+		self.config.exit_code = code;
+		self
+	}
+
+	/// Pick the exit code of errors implementing [`std::error::Error`]
+	/// based on what went wrong, e.g. using [`ExitCodeMap::sysexits`].
+	pub fn exit_code_map(mut self, map: ExitCodeMap) -> Self {
+		self.config.exit_code_map = Some(map);
+		self
+	}
+
+	/// Set the name of the program which is displayed in
+	/// front of every message, e.g. `myapp: <message>: <error>`.
+	pub fn app_name<S>(mut self, name: S) -> Self
+	where
+		S: Into<Cow<'static, str>>,
+	{
+		self.config.format.app_name = Some(name.into());
+		self
+	}
+
+	/// Collapse consecutive causes with the same message into one.
+	/// See [`FormatOptions::dedupe_causes`].
+	pub fn dedupe_causes(mut self, dedupe_causes: bool) -> Self {
+		self.config.format.dedupe_causes = dedupe_causes;
+		self
+	}
+
+	/// Display at most `max_causes` causes. See [`FormatOptions::max_causes`].
+	pub fn max_causes(mut self, max_causes: Option<usize>) -> Self {
+		self.config.format.max_causes = max_causes;
+		self
+	}
+
+	/// Display `error:` in front of every message, after the app name.
+	pub fn error_label(mut self, error_label: bool) -> Self {
+		self.config.format.error_label = error_label;
+		self
+	}
+
+	/// Display errors by writing them to `writer` instead of stderr,
+	/// e.g. to a log file or a buffer shown by a GUI.
+	///
+	/// Output written to `writer` isn't colored unless
+	/// [`color`](Self::color) is turned on explicitly.
+	pub fn writer<W>(mut self, writer: W) -> Self
+	where
+		W: io::Write + Send + 'static,
+	{
+		self.config.writer = Some(Writer::new(writer));
+		self
+	}
+
+	/// Display errors on stderr again after a [`writer`](Self::writer)
+	/// was set. This is the default.
+	pub fn stderr(mut self) -> Self {
+		self.config.writer = None;
+		self
+	}
+
+	/// Set how errors are displayed. Defaults to [`OutputMode::Human`].
+	pub fn output_mode(mut self, mode: OutputMode) -> Self {
+		self.config.output = mode;
+		self
+	}
+
+	/// Set how much of the error is displayed.
+	pub fn verbosity(mut self, verbosity: Verbosity) -> Self {
+		self.config.format.verbosity = verbosity;
+		self
+	}
+
+	/// Set how long lines are wrapped. Defaults to [`Wrap::Terminal`].
+	pub fn wrap(mut self, wrap: Wrap) -> Self {
+		self.config.format.wrap = wrap;
+		self
+	}
+
+	/// Lay out human-readable output using `formatter`
+	/// instead of the [`DefaultFormatter`](crate::DefaultFormatter).
+	pub fn formatter<F>(mut self, formatter: F) -> Self
+	where
+		F: ReportFormatter + 'static,
+	{
+		self.config.formatter = Some(Formatter::new(formatter));
+		self
+	}
+
+	/// Set how the program ends after giving up. Defaults to
+	/// [`Terminator::Exit`].
+	///
+	/// The error is displayed and the [exit hooks](crate::on_exit)
+	/// run before, regardless of the terminator.
+	pub fn terminator(mut self, terminator: Terminator) -> Self {
+		self.config.terminator = terminator;
+		self
+	}
+
+	/// Put the time an error occurred in front of its report as an
+	/// uncolored RFC 3339 timestamp in UTC, e.g. for tools which run
+	/// under cron or in long pipelines. Off by default.
+	/// # Example
+	/// ```rust
+	/// // Displays e.g. `2024-05-01T12:34:56Z Missing configuration file`.
+	/// giveup::config().timestamps(true).apply();
+	/// ```
+	pub fn timestamps(mut self, timestamps: bool) -> Self {
+		self.config.timestamps = timestamps;
+		self
+	}
+
+	/// Additionally emit an `::error` workflow command on stdout for
+	/// every displayed error, so it shows up as an annotation in GitHub
+	/// Actions runs. By default this is done if `GITHUB_ACTIONS=true`.
+	pub fn github_annotations(mut self, github_annotations: bool) -> Self {
+		self.config.github_annotations = Some(github_annotations);
+		self
+	}
+
+	/// Also send every displayed error to systemd-journald, or to
+	/// syslog if journald isn't running. The journal entry has the
+	/// error's hints, examples, etc. as separate fields.
+	///
+	/// Stderr still gets the human-readable error. By default this
+	/// is done if the process runs as a systemd service. Only
+	/// supported on Unix.
+	pub fn system_log(mut self, system_log: bool) -> Self {
+		self.config.system_log = Some(system_log);
+		self
+	}
+
+	/// Replace the hint which is added automatically if the last error
+	/// in the chain is an [`io::Error`] of the given kind.
+	///
+	/// There are built-in hints for `NotFound`, `PermissionDenied`,
+	/// `AlreadyExists`, `ConnectionRefused`, `TimedOut` and `AddrInUse`.
+	/// # Example
+	/// ```rust
+	/// use std::io::ErrorKind;
+	/// giveup::config()
+	///     .io_hint(ErrorKind::NotFound, "Run `myapp init` to create the file")
+	///     .no_io_hint(ErrorKind::PermissionDenied)
+	///     .apply();
+	/// ```
+	pub fn io_hint<S>(mut self, kind: io::ErrorKind, hint: S) -> Self
+	where
+		S: Into<Cow<'static, str>>,
+	{
+		self.config.io_hints.push((kind, Some(hint.into())));
+		self
+	}
+
+	/// Don't add a hint automatically for [`io::Error`]s of the given kind.
+	pub fn no_io_hint(mut self, kind: io::ErrorKind) -> Self {
+		self.config.io_hints.push((kind, None));
+		self
+	}
+
+	/// Display the [example commands for `shell`](crate::ShellExample)
+	/// instead of those for the shell the user runs.
+	pub fn shell(mut self, shell: Shell) -> Self {
+		self.config.shell = Some(shell);
+		self
+	}
+
+	/// Copy the first example command of the error to the clipboard
+	/// when giving up, and add a note saying so.
+	///
+	/// Nothing is copied unless errors are displayed on stderr and
+	/// it's a terminal. Only macOS and Windows keep the copied command
+	/// after the program exits, so nothing is copied on other platforms.
+	#[cfg(feature = "clipboard")]
+	pub fn copy_example(mut self, copy_example: bool) -> Self {
+		self.config.copy_example = copy_example;
+		self
+	}
+
+	/// After giving up, ask whether to run the first example command
+	/// of the error (e.g. ``Run `touch config.toml` now? [y/N]``) and
+	/// run it in the shell if the user answers yes. The program exits
+	/// afterwards, so it can be run again with the problem fixed.
+	/// Commands for [`Shell::Bash`](crate::Shell::Bash) are run with
+	/// `bash -c`, since they may use more than POSIX `sh` understands.
+	///
+	/// Nothing is asked unless both stdin and stderr are terminals
+	/// and errors are displayed on stderr.
+	pub fn interactive(mut self, interactive: bool) -> Self {
+		self.config.interactive = interactive;
+		self
+	}
+
+	/// Pass every message, cause, hint, etc. through `redact` before
+	/// it's displayed, e.g. to remove tokens or the user's home
+	/// directory (see [`redact_home_dir`](crate::redact_home_dir) and
+	/// [`shorten_paths`](crate::shorten_paths)).
+	///
+	/// The redacted report is also what's passed to the
+	/// [report hook](crate::set_report_hook) and written to crash reports.
+	/// # Example
+	/// ```rust
+	/// giveup::config()
+	///     .redact(|text| text.replace(env!("CARGO_PKG_NAME"), "<name>"))
+	///     .apply();
+	/// ```
+	pub fn redact<F>(mut self, redact: F) -> Self
+	where
+		F: Fn(&str) -> String + Send + Sync + 'static,
+	{
+		self.config.redactor = Some(Redactor::new(redact));
+		self
+	}
+
+	/// Write a detailed report to a file in the temporary directory
+	/// when giving up, and tell the user where to find it.
+	///
+	/// Besides all causes of the error, the file contains a backtrace,
+	/// the operating system, the command line arguments and `version`,
+	/// the version of the program (e.g. `env!("CARGO_PKG_VERSION")`),
+	/// so users can attach it to bug reports.
+	/// # Example
+	/// ```rust
+	/// giveup::config()
+	///     .app_name("myapp")
+	///     .crash_report(env!("CARGO_PKG_VERSION"))
+	///     .apply();
+	/// ```
+	pub fn crash_report<S>(mut self, version: S) -> Self
+	where
+		S: Into<Cow<'static, str>>,
+	{
+		self.config.crash_report = Some(version.into());
+		self
+	}
+
+	/// Set the text around the parts of the error, e.g. to translate it.
+	pub fn strings(mut self, strings: Strings) -> Self {
+		self.config.format.strings = strings;
+		self
+	}
+
+	/// Set all options used to format errors at once.
+	///
+	/// This overwrites the app name, verbosity, etc. set before.
+	pub fn format_options(mut self, opts: FormatOptions) -> Self {
+		self.config.format = opts;
+		self
+	}
+
+	/// Make this the configuration of the whole process.
+	pub fn apply(self) {
+		update_config(|config| *config = self.config);
+	}
+}
+
+/// Get a copy of the current configuration.
+pub(crate) fn current_config() -> Config {
+	CONFIG.read().unwrap_or_else(PoisonError::into_inner).clone()
+}
+
+/// The current configuration with the overrides users make through
+/// the environment applied, which is what errors are displayed with.
+///
+/// The overrides are never stored, so they don't end up in the
+/// configuration when it's changed using [`config`].
+pub(crate) fn effective_config() -> Config {
+	let mut config = current_config();
+	env_overrides().apply(&mut config);
+	config
+}
+
+// Overrides users make through the environment, e.g. to
+// raise the verbosity of reports for bug reports or to pick
+// the output they prefer without the program having to offer
+// flags for it.
+pub(crate) struct EnvOverrides {
+	verbose: bool,
+	style: Option<EnvStyle>,
+}
+
+// Output selected using `GIVEUP_STYLE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EnvStyle {
+	Plain,
+	Fancy,
+	Json,
+}
+
+impl EnvOverrides {
+	pub(crate) fn apply(&self, config: &mut Config) {
+		self.apply_format(&mut config.format);
+		match self.style {
+			Some(EnvStyle::Plain) => {
+				config.output = OutputMode::Human;
+				config.color = Some(false);
+			},
+			Some(EnvStyle::Fancy) => {
+				config.output = OutputMode::Human;
+				// Writers set by the program aren't terminals.
+				if config.writer.is_none() {
+					config.color = Some(true);
+				}
+			},
+			Some(EnvStyle::Json) => config.output = OutputMode::Json,
+			None => {},
+		}
+	}
+
+	pub(crate) fn apply_format(&self, opts: &mut FormatOptions) {
+		if self.verbose {
+			opts.verbosity = Verbosity::Verbose;
+		}
+	}
+}
+
+// The overrides, which are read from the environment once.
+pub(crate) fn env_overrides() -> &'static EnvOverrides {
+	static OVERRIDES: OnceLock<EnvOverrides> = OnceLock::new();
+	OVERRIDES.get_or_init(|| {
+		let var = |name| std::env::var_os(name).map(|value| value.to_string_lossy().into_owned());
+		EnvOverrides {
+			verbose: env_debug(var("GIVEUP_DEBUG").as_deref()),
+			style: env_style(var("GIVEUP_STYLE").as_deref()),
+		}
+	})
+}
+
+/// Change the current configuration.
+pub(crate) fn update_config<F>(f: F)
+where
+	F: FnOnce(&mut Config),
+{
+	f(&mut CONFIG.write().unwrap_or_else(PoisonError::into_inner));
+}
+
+/// Exit code used by errors which don't pick their own.
+pub(crate) fn default_exit_code() -> i32 {
+	CONFIG.read().unwrap_or_else(PoisonError::into_inner).exit_code
+}
+
+impl Config {
+	/// Whether the output should be styled.
+	pub(crate) fn color_enabled(&self) -> bool {
+		if !cfg!(feature = "color") {
+			return false;
+		}
+		match self.writer {
+			// The environment only says something about stderr.
+			Some(_) => self.color.unwrap_or(false),
+			// Consoles which can't display styling get plain output.
+			None => self.color.unwrap_or_else(color_from_env) && console::enable_ansi(),
+		}
+	}
+
+	/// Whether GitHub Actions annotations are emitted.
+	pub(crate) fn github_annotations_enabled(&self) -> bool {
+		self.github_annotations
+			.unwrap_or_else(|| std::env::var_os("GITHUB_ACTIONS").is_some_and(|value| value == "true"))
+	}
+
+	/// Whether errors are sent to journald or syslog.
+	#[cfg(unix)]
+	pub(crate) fn system_log_enabled(&self) -> bool {
+		self.system_log.unwrap_or_else(crate::system_log::runs_as_service)
+	}
+
+	/// The report as it's displayed, with all text redacted.
+	pub(crate) fn redacted<'a>(&self, report: &'a ErrorReport) -> Cow<'a, ErrorReport> {
+		match &self.redactor {
+			Some(redactor) => Cow::Owned(redactor.redact(report)),
+			None => Cow::Borrowed(report),
+		}
+	}
+
+	/// The app name, or the name of the executable.
+	pub(crate) fn program_name(&self) -> String {
+		if let Some(app_name) = &self.format.app_name {
+			return app_name.to_string();
+		}
+		std::env::args_os().next()
+			.as_ref()
+			.and_then(|arg| std::path::Path::new(arg).file_name())
+			.map_or_else(|| "giveup".to_owned(), |name| name.to_string_lossy().into_owned())
+	}
+
+	/// The configuration used to display errors on stderr, where
+	/// [`Wrap::Terminal`] wraps lines at the width of the terminal.
+	pub(crate) fn for_stderr(&self) -> Config {
+		let mut config = self.clone();
+		if config.format.wrap == Wrap::Terminal && std::io::stderr().is_terminal() {
+			config.format.wrap = Wrap::Width(terminal_width());
+		}
+		config
+	}
+
+	/// Capture a backtrace of the caller if backtraces are enabled.
+	pub(crate) fn capture_backtrace(&self) -> Backtrace {
+		match self.backtrace {
+			None => Backtrace::capture(),
+			Some(true) => Backtrace::force_capture(),
+			Some(false) => Backtrace::disabled(),
+		}
+	}
+}
+
+/// Decide whether to style the output based on the `NO_COLOR`,
+/// `CLICOLOR_FORCE` and `CLICOLOR` conventions.
+fn color_from_env() -> bool {
+	let var = |name| std::env::var_os(name).map(|value| value.to_string_lossy().into_owned());
+	env_color(
+		var("NO_COLOR").as_deref(),
+		var("CLICOLOR_FORCE").as_deref(),
+		var("CLICOLOR").as_deref(),
+		std::io::stderr().is_terminal(),
+	)
+}
+
+/// Width of the terminal stderr is displayed on.
+fn terminal_width() -> usize {
+	let columns = std::env::var("COLUMNS").ok().and_then(|columns| columns.parse().ok());
+	columns.filter(|&columns| columns > 0)
+		.or_else(terminal_size::stderr_width)
+		.unwrap_or(80)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "freebsd"))]
+mod terminal_size {
+	use std::os::raw::{c_int, c_ulong, c_ushort};
+
+	#[cfg(any(target_os = "linux", target_os = "android"))]
+	const TIOCGWINSZ: c_ulong = 0x5413;
+	#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+	const TIOCGWINSZ: c_ulong = 0x4008_7468;
+
+	#[repr(C)]
+	#[derive(Default)]
+	struct WinSize {
+		rows: c_ushort,
+		columns: c_ushort,
+		x_pixels: c_ushort,
+		y_pixels: c_ushort,
+	}
+
+	extern "C" {
+		fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+	}
+
+	pub(super) fn stderr_width() -> Option<usize> {
+		let mut size = WinSize::default();
+		// SAFETY: `TIOCGWINSZ` only writes a `struct winsize` to the pointer.
+		let res = unsafe { ioctl(2, TIOCGWINSZ, &mut size as *mut WinSize) };
+		(res == 0 && size.columns > 0).then_some(usize::from(size.columns))
+	}
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "freebsd")))]
+mod terminal_size {
+	pub(super) fn stderr_width() -> Option<usize> {
+		None
+	}
+}
+
+#[cfg(windows)]
+mod console {
+	use std::os::raw::{c_int, c_void};
+	use std::sync::OnceLock;
+
+	const STD_ERROR_HANDLE: u32 = -12i32 as u32;
+	const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+
+	#[link(name = "kernel32")]
+	extern "system" {
+		fn GetStdHandle(std_handle: u32) -> *mut c_void;
+		fn GetConsoleMode(console: *mut c_void, mode: *mut u32) -> c_int;
+		fn SetConsoleMode(console: *mut c_void, mode: u32) -> c_int;
+	}
+
+	// Turn on the interpretation of escape sequences by the console
+	// stderr is attached to, which legacy consoles (e.g. `cmd.exe` before
+	// Windows 10) don't support. This is only tried once.
+	pub(super) fn enable_ansi() -> bool {
+		static ENABLED: OnceLock<bool> = OnceLock::new();
+		*ENABLED.get_or_init(|| {
+			let mut mode = 0;
+			// SAFETY: The handle is only passed on to the console functions,
+			// which fail for anything but a console.
+			unsafe {
+				let handle = GetStdHandle(STD_ERROR_HANDLE);
+				if GetConsoleMode(handle, &mut mode) == 0 {
+					// Not a console, e.g. a pipe or a terminal emulator
+					// like mintty, which handles escape sequences itself.
+					return true;
+				}
+				mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0
+					|| SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
+			}
+		})
+	}
+}
+
+#[cfg(not(windows))]
+mod console {
+	// Terminals elsewhere interpret escape sequences anyway.
+	pub(super) fn enable_ansi() -> bool {
+		true
+	}
+}
+
+// `NO_COLOR` set to anything but an empty string always turns color
+// off. Otherwise `CLICOLOR_FORCE` set to anything but `0` turns it on,
+// even if stderr isn't a terminal. `CLICOLOR=0` turns it off, too.
+fn env_color(
+	no_color: Option<&str>,
+	clicolor_force: Option<&str>,
+	clicolor: Option<&str>,
+	is_terminal: bool,
+) -> bool {
+	if no_color.is_some_and(|value| !value.is_empty()) {
+		false
+	} else if clicolor_force.is_some_and(|value| value != "0") {
+		true
+	} else if clicolor == Some("0") {
+		false
+	} else {
+		is_terminal
+	}
+}
+
+// `GIVEUP_STYLE` set to `plain` or `fancy` selects uncolored or
+// colored human-readable output, `json` selects JSON output. Other
+// values are ignored.
+fn env_style(giveup_style: Option<&str>) -> Option<EnvStyle> {
+	match giveup_style? {
+		"plain" => Some(EnvStyle::Plain),
+		"fancy" => Some(EnvStyle::Fancy),
+		"json" => Some(EnvStyle::Json),
+		_ => None,
+	}
+}
+
+// `GIVEUP_DEBUG` set to anything but an empty string, `0`
+// or `false` turns on verbose output.
+fn env_debug(giveup_debug: Option<&str>) -> bool {
+	giveup_debug.is_some_and(|value| !matches!(value, "" | "0" | "false"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::backtrace::BacktraceStatus;
+
+	#[test]
+	fn backtraces_can_be_forced() {
+		let builder = config().backtrace(true);
+		assert_eq!(builder.config.capture_backtrace().status(), BacktraceStatus::Captured);
+		let builder = builder.backtrace(false);
+		assert_eq!(builder.config.capture_backtrace().status(), BacktraceStatus::Disabled);
+	}
+
+	#[test]
+	fn builder_starts_with_the_current_config() {
+		let builder = config();
+		assert_eq!(builder.config.exit_code, current_config().exit_code);
+	}
+
+	#[test]
+	fn builder_sets_all_values() {
+		let builder = ConfigBuilder { config: DEFAULT_CONFIG }
+			.color(false)
+			.exit_code(2)
+			.app_name("myapp")
+			.error_label(true)
+			.github_annotations(true)
+			.verbosity(Verbosity::Quiet);
+		assert_eq!(builder.config.color, Some(false));
+		assert!(!builder.config.color_enabled());
+		assert_eq!(builder.config.exit_code, 2);
+		assert_eq!(builder.config.format.app_name.as_deref(), Some("myapp"));
+		assert!(builder.config.format.error_label);
+		assert!(builder.config.github_annotations_enabled());
+		assert_eq!(builder.config.format.verbosity, Verbosity::Quiet);
+	}
+
+	#[test]
+	fn forced_color_needs_the_color_feature() {
+		let builder = ConfigBuilder { config: DEFAULT_CONFIG }.color(true);
+		assert_eq!(builder.config.color_enabled(), cfg!(feature = "color"));
+	}
+
+	#[test]
+	fn terminals_are_colored_by_default() {
+		assert!(env_color(None, None, None, true));
+		assert!(!env_color(None, None, None, false));
+		assert!(env_color(None, None, Some("1"), true));
+	}
+
+	#[test]
+	fn no_color_turns_color_off() {
+		assert!(!env_color(Some("1"), None, None, true));
+		assert!(!env_color(Some("1"), Some("1"), None, true));
+		// An empty `NO_COLOR` is ignored.
+		assert!(env_color(Some(""), None, None, true));
+	}
+
+	#[test]
+	fn clicolor_force_turns_color_on() {
+		assert!(env_color(None, Some("1"), None, false));
+		assert!(env_color(None, Some("1"), Some("0"), false));
+		assert!(!env_color(None, Some("0"), None, false));
+	}
+
+	#[test]
+	fn giveup_debug_overrides_the_verbosity() {
+		let mut config = DEFAULT_CONFIG;
+		EnvOverrides { verbose: true, style: None }.apply(&mut config);
+		assert_eq!(config.format.verbosity, Verbosity::Verbose);
+	}
+
+	#[test]
+	fn giveup_debug_turns_verbose_output_on() {
+		assert!(env_debug(Some("1")));
+		assert!(env_debug(Some("true")));
+		assert!(!env_debug(Some("0")));
+		assert!(!env_debug(Some("")));
+		assert!(!env_debug(None));
+	}
+
+	#[test]
+	fn style_can_be_selected_by_users() {
+		let overrides = |style| EnvOverrides { verbose: false, style: env_style(style) };
+		let mut config = DEFAULT_CONFIG;
+		overrides(Some("json")).apply(&mut config);
+		assert_eq!(config.output, OutputMode::Json);
+		overrides(Some("fancy")).apply(&mut config);
+		assert_eq!((config.output, config.color), (OutputMode::Human, Some(true)));
+		overrides(Some("plain")).apply(&mut config);
+		assert_eq!((config.output, config.color), (OutputMode::Human, Some(false)));
+		overrides(Some("unknown")).apply(&mut config);
+		overrides(None).apply(&mut config);
+		assert_eq!((config.output, config.color), (OutputMode::Human, Some(false)));
+	}
+
+	#[test]
+	fn fancy_style_leaves_writers_uncolored() {
+		let mut config = Config {
+			writer: Some(Writer::new(std::io::sink())),
+			..DEFAULT_CONFIG
+		};
+		EnvOverrides { verbose: false, style: Some(EnvStyle::Fancy) }.apply(&mut config);
+		assert_eq!(config.color, None);
+	}
+
+	#[test]
+	fn clicolor_zero_turns_color_off() {
+		assert!(!env_color(None, None, Some("0"), true));
+	}
+}
@@ -0,0 +1,122 @@
+//! Files with everything known about the error the program gave
+//! up on, which users can attach to bug reports.
+
+use crate::config::{Config, OutputMode};
+use crate::options::{Verbosity, Wrap};
+use crate::output::format_report;
+use crate::report::{backtrace_string, ErrorReport};
+use std::backtrace::Backtrace;
+use std::collections::hash_map::RandomState;
+use std::fs::OpenOptions;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// The report with a note about where the crash report was written,
+// if crash reports are enabled and writing it succeeded.
+pub(crate) fn with_crash_report(report: &ErrorReport, config: &Config) -> ErrorReport {
+	let mut report = report.clone();
+	let Some(version) = &config.crash_report else {
+		return report;
+	};
+	let contents = crash_report(&report, config, version);
+	if let Ok(path) = write_crash_report(config, &contents) {
+		report.notes.push(format!("A detailed report was written to {}", path.display()));
+	}
+	report
+}
+
+// Write `contents` to a new file in the temporary directory, which only
+// the user can read since it contains the program's arguments.
+fn write_crash_report(config: &Config, contents: &str) -> io::Result<PathBuf> {
+	let mut options = OpenOptions::new();
+	// Never follow or truncate a file someone else created in its place.
+	options.write(true).create_new(true);
+	#[cfg(unix)]
+	std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+	let path = crash_report_path(config);
+	options.open(&path)?.write_all(contents.as_bytes())?;
+	Ok(path)
+}
+
+// Path in the temporary directory with a random part,
+// so it can't be guessed by other users.
+fn crash_report_path(config: &Config) -> PathBuf {
+	let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+	// The keys of `RandomState` are seeded randomly by the OS.
+	let random = RandomState::new().build_hasher().finish();
+	let name = format!("{}-report-{timestamp}-{}-{random:016x}.txt", config.program_name(), std::process::id());
+	std::env::temp_dir().join(name)
+}
+
+// Details about the program followed by the uncolored, verbose report.
+fn crash_report(report: &ErrorReport, config: &Config, version: &str) -> String {
+	let args = format!("{:?}", std::env::args_os().collect::<Vec<_>>());
+	// The report itself is redacted already.
+	let args = match &config.redactor {
+		Some(redactor) => redactor.redact_text(&args),
+		None => args,
+	};
+	let mut contents = format!(
+		"name: {}\nversion: {version}\nos: {} {}\nargs: {args}\n\n",
+		config.program_name(),
+		std::env::consts::OS,
+		std::env::consts::ARCH,
+	);
+	let report = ErrorReport {
+		backtrace: report.backtrace.clone().or_else(|| backtrace_string(&Backtrace::force_capture())),
+		..report.clone()
+	};
+	let mut config = Config {
+		output: OutputMode::Human,
+		formatter: None,
+		..config.clone()
+	};
+	config.format.verbosity = Verbosity::Verbose;
+	config.format.wrap = Wrap::Never;
+	contents.push_str(&format_report(&report, &config, false));
+	contents
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::current_config;
+
+	#[test]
+	fn crash_reports_are_verbose() {
+		let mut config = current_config();
+		config.format.verbosity = Verbosity::Quiet;
+		config.format.app_name = Some("myapp".into());
+		let report = ErrorReport {
+			primary: "error".to_owned(),
+			causes: vec!["cause".to_owned()],
+			..ErrorReport::with_message("message")
+		};
+		let contents = crash_report(&report, &config, "1.2.0");
+		assert!(contents.starts_with("name: myapp\nversion: 1.2.0\nos: "));
+		assert!(contents.contains("\n\nmyapp: message: error\nCaused by: cause\nBacktrace:\n"));
+	}
+
+	#[test]
+	fn crash_reports_are_private_and_unpredictable() {
+		let config = current_config();
+		assert_ne!(crash_report_path(&config), crash_report_path(&config));
+		let path = write_crash_report(&config, "contents").unwrap();
+		assert_eq!(std::fs::read_to_string(&path).unwrap(), "contents");
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+			let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+			assert_eq!(mode & 0o777, 0o600);
+		}
+		std::fs::remove_file(path).unwrap();
+	}
+
+	#[test]
+	fn nothing_is_written_by_default() {
+		let report = ErrorReport::with_message("message");
+		assert!(with_crash_report(&report, &current_config()).notes.is_empty());
+	}
+}
@@ -0,0 +1,174 @@
+use crate::adapter::ErrorAdapter;
+use crate::exit_code_map::error_exit_code;
+use crate::giveup::{automatic_hints, cause_messages, verbose_debug, GiveupExitCode, GiveupFormatError};
+use crate::options::FormatOptions;
+use crate::report::ErrorReport;
+use miette::{Diagnostic, LabeledSpan, Severity, SourceCode};
+
+/// Giving up on a `miette::Report` displays the diagnostic it wraps.
+///
+/// Any other [`Diagnostic`] is given up on like a std error, or by
+/// converting it into a `miette::Report` first to display all of it,
+/// e.g. using `?` in a function returning `miette::Result`. The code
+/// of the diagnostic is displayed as `error[<code>]:`, the help text
+/// as a hint, its URL as a link and its labels as notes which point
+/// into the source code.
+impl GiveupFormatError for ErrorAdapter<miette::Report> {
+	fn segments(&self, opts: &FormatOptions) -> ErrorReport {
+		let inner: &miette::Report = self.as_ref();
+		let diagnostic: &dyn Diagnostic = inner.as_ref();
+		let outer_most: &dyn std::error::Error = inner.as_ref();
+
+		let primary = if opts.prefer_debug {
+			format!("{outer_most:?}")
+		} else {
+			inner.to_string()
+		};
+
+		let hints = automatic_hints(outer_most);
+		let mut report = ErrorReport {
+			code: diagnostic.code().map(|code| code.to_string()),
+			primary,
+			causes: cause_messages(outer_most),
+			auto_hints: hints.len(),
+			hints,
+			debug: verbose_debug(outer_most, opts),
+			..Default::default()
+		};
+
+		report.hints.extend(diagnostic.help().map(|help| help.to_string()));
+		match diagnostic.severity() {
+			Some(Severity::Warning) => report.notes.push("severity: warning".to_owned()),
+			Some(Severity::Advice) => report.notes.push("severity: advice".to_owned()),
+			_ => {},
+		}
+		if let (Some(source), Some(labels)) = (diagnostic.source_code(), diagnostic.labels()) {
+			report.notes.extend(labels.filter_map(|label| label_note(source, &label)));
+		}
+		report.links.extend(diagnostic.url().map(|url| url.to_string()));
+		report
+	}
+}
+
+impl GiveupExitCode for ErrorAdapter<miette::Report> {
+	fn exit_code(&self) -> i32 {
+		let inner: &miette::Report = self.as_ref();
+		error_exit_code(inner.as_ref())
+	}
+}
+
+// Note which shows the line `label` points to, e.g.:
+//
+// config.toml:3:8: expected a number
+//     port = "abc"
+//            ^^^^^
+fn label_note(source: &dyn SourceCode, label: &LabeledSpan) -> Option<String> {
+	// Without context the contents start at the label, and the column
+	// is the byte offset of the label in its line.
+	let contents = source.read_span(label.inner(), 0, 0).ok()?;
+	let start = contents.column();
+	let line_start = label.offset().checked_sub(start)?;
+
+	// One line of context after the label makes the contents
+	// include the rest of the line.
+	let line_span = (line_start, start + label.len()).into();
+	let line_contents = source.read_span(&line_span, 0, 1).ok()?;
+	let data = String::from_utf8_lossy(line_contents.data());
+	let line = data.lines().next().unwrap_or_default();
+	let end = (start + label.len()).min(line.len());
+	let column = line.get(..start)?.chars().count();
+	let width = line.get(start..end)?.chars().count().max(1);
+
+	let location = match contents.name() {
+		Some(name) => format!("{name}:{}:{}", contents.line() + 1, column + 1),
+		None => format!("line {}, column {}", contents.line() + 1, column + 1),
+	};
+	let text = label.label().unwrap_or("here");
+	Some(format!(
+		"{location}: {text}\n    {line}\n    {}{}",
+		" ".repeat(column),
+		"^".repeat(width),
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use miette::{NamedSource, SourceSpan};
+	use std::error::Error;
+	use std::fmt;
+
+	#[derive(Debug)]
+	struct ConfigError {
+		src: NamedSource<String>,
+		span: SourceSpan,
+	}
+
+	impl fmt::Display for ConfigError {
+		fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+			f.write_str("Invalid configuration")
+		}
+	}
+
+	impl Error for ConfigError {}
+
+	impl Diagnostic for ConfigError {
+		fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+			Some(Box::new("config::invalid"))
+		}
+
+		fn severity(&self) -> Option<Severity> {
+			Some(Severity::Warning)
+		}
+
+		fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+			Some(Box::new("Ports are numbers"))
+		}
+
+		fn url<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+			Some(Box::new("https://example.com/config"))
+		}
+
+		fn source_code(&self) -> Option<&dyn SourceCode> {
+			Some(&self.src)
+		}
+
+		fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+			Some(Box::new(std::iter::once(LabeledSpan::new_with_span(
+				Some("expected a number".to_owned()),
+				self.span,
+			))))
+		}
+	}
+
+	fn config_error() -> ErrorAdapter<miette::Report> {
+		let src = "name = \"app\"\nport = \"abc\"\n";
+		ErrorAdapter::new(miette::Report::new(ConfigError {
+			src: NamedSource::new("config.toml", src.to_owned()),
+			span: (20, 5).into(),
+		}))
+	}
+
+	#[test]
+	fn diagnostics_are_displayed() {
+		let report = config_error().segments(&FormatOptions::default());
+		assert_eq!(report.code.as_deref(), Some("config::invalid"));
+		assert_eq!(report.primary, "Invalid configuration");
+		assert_eq!(report.hints, vec!["Ports are numbers"]);
+		assert_eq!(report.links, vec!["https://example.com/config"]);
+		assert_eq!(report.notes, vec![
+			"severity: warning",
+			"config.toml:2:8: expected a number\n    port = \"abc\"\n           ^^^^^",
+		]);
+	}
+
+	#[test]
+	fn plain_diagnostics_are_displayed_like_errors() {
+		let err = miette::Report::msg("Something failed").wrap_err("Failed to start");
+		let report = ErrorAdapter::new(err).segments(&FormatOptions::default());
+		assert_eq!(report.primary, "Failed to start");
+		assert_eq!(report.causes, vec!["Something failed"]);
+		assert!(report.hints.is_empty());
+		assert!(report.notes.is_empty());
+	}
+}
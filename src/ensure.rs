@@ -0,0 +1,127 @@
+use crate::config::default_exit_code;
+use crate::output::exit_gracefully;
+use crate::report::ErrorReport;
+use std::fmt::Display;
+
+/// Terminate the program gracefully and display `msg`.
+///
+/// This is for cases where there is no `Result` or `Option`
+/// to call [`giveup`](crate::Giveup::giveup) on. See
+/// [`giveup!`](crate::giveup!) for formatted messages.
+/// # Example
+/// ```rust no_run
+/// let args: Vec<String> = std::env::args().collect();
+/// if args.len() > 2 {
+///     giveup::giveup("Too many arguments");
+/// }
+/// ```
+pub fn giveup<M>(msg: M) -> !
+where
+	M: Display,
+{
+	exit_gracefully(&ErrorReport::with_message(&msg.to_string()), default_exit_code())
+}
+
+/// Terminate the program gracefully and display `msg`
+/// if `cond` is false.
+///
+/// This is the `giveup` counterpart to `assert!` for
+/// conditions which depend on user input.
+/// # Example
+/// ```rust
+/// use giveup::giveup_ensure;
+/// let port: u32 = 8080;
+/// giveup_ensure(port < 65536, "The port must be less than 65536");
+/// ```
+#[inline]
+pub fn giveup_ensure<M>(cond: bool, msg: M)
+where
+	M: Display,
+{
+	if !cond {
+		giveup(msg)
+	}
+}
+
+/// Same as [`giveup_ensure`], but the message is only
+/// constructed if `cond` is false.
+#[inline]
+pub fn giveup_ensure_with<F, M>(cond: bool, msg: F)
+where
+	F: FnOnce() -> M,
+	M: Display,
+{
+	if !cond {
+		giveup(msg())
+	}
+}
+
+/// Terminate the program gracefully and display a formatted
+/// message if a condition is false.
+///
+/// The message is only formatted if the condition is false.
+/// # Example
+/// ```rust
+/// let port: u32 = 8080;
+/// giveup::giveup_ensure!(port < 65536, "The port {port} must be less than 65536");
+/// ```
+#[macro_export]
+macro_rules! giveup_ensure {
+	($cond:expr, $($arg:tt)+) => {
+		$crate::giveup_ensure_with($cond, || ::std::format!($($arg)+))
+	};
+}
+
+/// Terminate the program gracefully and display a formatted message.
+/// # Example
+/// ```rust no_run
+/// let path = "config.toml";
+/// giveup::giveup!("Failed to open {path}");
+/// ```
+#[macro_export]
+macro_rules! giveup {
+	($($arg:tt)+) => {
+		$crate::giveup(::std::format!($($arg)+))
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::capture;
+
+	#[test]
+	fn nothing_is_displayed_if_the_condition_holds() {
+		let captured = capture(|| {
+			giveup_ensure(true, "message");
+			giveup_ensure_with(true, || "message");
+			crate::giveup_ensure!(true, "{}", "message");
+		});
+		assert!(captured.is_empty());
+	}
+
+	#[test]
+	fn the_message_is_displayed_if_the_condition_fails() {
+		let captured = capture(|| giveup_ensure(false, "message"));
+		assert_eq!(captured, vec![("message\n".to_owned(), 1)]);
+	}
+
+	#[test]
+	fn lazy_messages_are_displayed_if_the_condition_fails() {
+		let captured = capture(|| giveup_ensure_with(false, || format!("{}", 42)));
+		assert_eq!(captured, vec![("42\n".to_owned(), 1)]);
+		let captured = capture(|| crate::giveup_ensure!(1 > 2, "{} > {}", 1, 2));
+		assert_eq!(captured, vec![("1 > 2\n".to_owned(), 1)]);
+	}
+
+	#[test]
+	fn giving_up_displays_the_message() {
+		let captured = capture(|| giveup("message"));
+		assert_eq!(captured, vec![("message\n".to_owned(), 1)]);
+		let captured = capture(|| giveup(format_args!("port {}", 80)));
+		assert_eq!(captured, vec![("port 80\n".to_owned(), 1)]);
+		let path = "config.toml";
+		let captured = capture(|| crate::giveup!("Failed to open {path} ({})", 42));
+		assert_eq!(captured, vec![("Failed to open config.toml (42)\n".to_owned(), 1)]);
+	}
+}
@@ -0,0 +1,208 @@
+use crate::config::{effective_config, Terminator};
+use crate::giveup::{giveup_report, GiveupFormatError};
+use crate::output::{report_failure, terminate};
+use crate::report::ErrorReport;
+use std::fmt::Display;
+use std::process::{ExitCode, Termination};
+
+/// Return type for `main` which displays errors like
+/// [`giveup`](crate::Giveup::giveup) does.
+///
+/// `main` returning `Result<(), E>` always displays `Error: ` followed
+/// by the error's `Debug` representation and exits with code 1. Instead,
+/// `Exit` displays the formatted error including its hints and exits
+/// with the error's [`exit_code`](crate::GiveupExitCode::exit_code).
+/// Everything else happens like when giving up, e.g. the
+/// [`on_exit`](crate::on_exit) hooks run, a crash report is written and
+/// a [`Terminator`] other than [`Terminator::Exit`] ends the program.
+///
+/// Errors without an implementation of [`GiveupFormatError`], e.g.
+/// `anyhow::Error` or boxed errors, have to be wrapped in an
+/// [`ErrorAdapter`](crate::ErrorAdapter), which `?` does on its own.
+/// # Example
+/// ```rust no_run
+/// use giveup::{Exit, Giveup, HintedError};
+///
+/// fn run() -> Result<(), HintedError<std::io::Error>> {
+///     let config = std::fs::read_to_string("config-path")
+///         .hint("Create a configuration file")?;
+///     Ok(())
+/// }
+///
+/// fn main() -> Exit<HintedError<std::io::Error>> {
+///     Exit::with_message(run(), "Failed to start")
+/// }
+/// ```
+///
+/// Use [`run`](Exit::run) to use `?` in `main` itself.
+/// ```rust no_run
+/// use giveup::Exit;
+///
+/// fn main() -> Exit<std::io::Error> {
+///     Exit::run(|| {
+///         let config = std::fs::read_to_string("config-path")?;
+///         Ok(())
+///     })
+/// }
+/// ```
+///
+/// The same works with boxed errors and those of `anyhow` and `eyre`.
+/// ```rust no_run
+/// use giveup::{ErrorAdapter, Exit};
+/// use std::error::Error;
+///
+/// fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
+///     let config = std::fs::read_to_string("config-path")?;
+///     Ok(())
+/// }
+///
+/// fn main() -> Exit<ErrorAdapter<Box<dyn Error + Send + Sync>>> {
+///     Exit::run(|| {
+///         run()?;
+///         Ok(())
+///     })
+/// }
+/// ```
+#[derive(Debug)]
+#[must_use = "the error is only displayed once `Exit` is returned from `main`"]
+pub struct Exit<E> {
+	result: Result<(), E>,
+	msg: String,
+}
+
+impl<E> Exit<E> {
+	/// Display only the error if `result` is an error.
+	pub fn new(result: Result<(), E>) -> Self {
+		Self::with_message(result, "")
+	}
+
+	/// Display `msg` in front of the error, like
+	/// [`giveup`](crate::Giveup::giveup) does.
+	pub fn with_message<S>(result: Result<(), E>, msg: S) -> Self
+	where
+		S: Display,
+	{
+		Self {
+			result,
+			msg: msg.to_string(),
+		}
+	}
+
+	/// Display only the error if `f` returns one.
+	pub fn run<F>(f: F) -> Self
+	where
+		F: FnOnce() -> Result<(), E>,
+	{
+		Self::new(f())
+	}
+
+	/// Display `msg` in front of the error if `f` returns one.
+	pub fn run_with_message<F, S>(f: F, msg: S) -> Self
+	where
+		F: FnOnce() -> Result<(), E>,
+		S: Display,
+	{
+		Self::with_message(f(), msg)
+	}
+}
+
+impl<E> From<Result<(), E>> for Exit<E> {
+	fn from(result: Result<(), E>) -> Self {
+		Self::new(result)
+	}
+}
+
+impl<E> Exit<E>
+where
+	E: GiveupFormatError,
+{
+	// The report to display and the code to exit with if there is an error.
+	fn failure(&self) -> Option<(ErrorReport, i32)> {
+		let e = self.result.as_ref().err()?;
+		Some((giveup_report(e, &self.msg), e.exit_code()))
+	}
+}
+
+impl<E> Termination for Exit<E>
+where
+	E: GiveupFormatError,
+{
+	fn report(self) -> ExitCode {
+		match self.failure() {
+			None => ExitCode::SUCCESS,
+			Some((report, code)) => {
+				let config = effective_config();
+				let report = report_failure(&report, code, &config);
+				match config.terminator {
+					Terminator::Exit => exit_code(code),
+					terminator => terminate(terminator, &report, code),
+				}
+			},
+		}
+	}
+}
+
+// `ExitCode` only holds a byte. Codes which don't fit still fail
+// instead of being truncated, e.g. 256 to 0.
+fn exit_code(code: i32) -> ExitCode {
+	match u8::try_from(code) {
+		Ok(code) => ExitCode::from(code),
+		Err(_) => ExitCode::FAILURE,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{GiveupExitCode, MessageError};
+	use std::error::Error;
+	use std::fmt;
+
+	#[derive(Debug)]
+	struct UsageError;
+
+	impl fmt::Display for UsageError {
+		fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+			f.write_str("Invalid arguments")
+		}
+	}
+
+	impl Error for UsageError {}
+
+	#[test]
+	fn success_exits_with_success() {
+		assert_eq!(Exit::<MessageError>::new(Ok(())).report(), ExitCode::SUCCESS);
+	}
+
+	#[test]
+	fn errors_are_displayed_with_the_message() {
+		let exit = Exit::with_message(Err(UsageError), "Failed to start");
+		let (report, code) = exit.failure().unwrap();
+		assert_eq!(report.message, "Failed to start");
+		assert_eq!(report.primary, "Invalid arguments");
+		assert_eq!(code, UsageError.exit_code());
+	}
+
+	#[test]
+	fn codes_which_do_not_fit_fail() {
+		assert_eq!(exit_code(65), ExitCode::from(65));
+		assert_eq!(exit_code(256), ExitCode::FAILURE);
+		assert_eq!(exit_code(-1), ExitCode::FAILURE);
+	}
+
+	#[test]
+	fn failures_are_reported_like_giving_up() {
+		let captured = crate::testing::capture(|| {
+			let _ = Exit::run_with_message(|| Err(UsageError), "Failed to start").report();
+		});
+		assert_eq!(captured, vec![("Failed to start: Invalid arguments\n".to_owned(), 1)]);
+	}
+
+	#[test]
+	fn errors_can_be_displayed_without_a_message() {
+		let exit = Exit::from(Err::<(), _>(MessageError::new("Invalid arguments")));
+		let (report, _) = exit.failure().unwrap();
+		let output = crate::output::format_report(&report, &crate::config::current_config(), false);
+		assert_eq!(output, "Invalid arguments\n");
+	}
+}
@@ -0,0 +1,211 @@
+use crate::config::current_config;
+use crate::giveup::sources;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, ErrorKind};
+use std::sync::Arc;
+
+/// Exit codes of BSD's `sysexits.h`, which tell scripts
+/// what kind of failure occurred.
+pub mod sysexits {
+	/// The command was used incorrectly, e.g. with wrong arguments.
+	pub const EX_USAGE: i32 = 64;
+	/// The input data was incorrect in some way.
+	pub const EX_DATAERR: i32 = 65;
+	/// An input file didn't exist or wasn't readable.
+	pub const EX_NOINPUT: i32 = 66;
+	/// The specified user didn't exist.
+	pub const EX_NOUSER: i32 = 67;
+	/// The specified host didn't exist.
+	pub const EX_NOHOST: i32 = 68;
+	/// A service is unavailable.
+	pub const EX_UNAVAILABLE: i32 = 69;
+	/// An internal software error was detected.
+	pub const EX_SOFTWARE: i32 = 70;
+	/// An operating system error was detected.
+	pub const EX_OSERR: i32 = 71;
+	/// A system file didn't exist, couldn't be opened or was invalid.
+	pub const EX_OSFILE: i32 = 72;
+	/// A (user specified) output file couldn't be created.
+	pub const EX_CANTCREAT: i32 = 73;
+	/// An error occurred while doing I/O on some file.
+	pub const EX_IOERR: i32 = 74;
+	/// A temporary failure, the user is invited to retry.
+	pub const EX_TEMPFAIL: i32 = 75;
+	/// The remote system returned something invalid.
+	pub const EX_PROTOCOL: i32 = 76;
+	/// Insufficient permission to perform the operation.
+	pub const EX_NOPERM: i32 = 77;
+	/// Something was found in an unconfigured or misconfigured state.
+	pub const EX_CONFIG: i32 = 78;
+}
+
+type Classifier = Arc<dyn Fn(&(dyn Error + 'static)) -> Option<i32> + Send + Sync>;
+
+#[derive(Clone)]
+enum Rule {
+	IoKind(ErrorKind, i32),
+	Classify(Classifier),
+}
+
+/// Rules which pick the exit code of errors implementing
+/// [`std::error::Error`] based on what went wrong.
+///
+/// Each error in the chain is checked against the rules in the order
+/// they were added, starting with the outer most error, and the first
+/// matching rule decides. Errors no rule matches exit with the default
+/// exit code. Install a map using
+/// [`ConfigBuilder::exit_code_map`](crate::ConfigBuilder::exit_code_map).
+///
+/// # Example
+/// ```rust
+/// use giveup::sysexits::EX_CONFIG;
+/// use giveup::ExitCodeMap;
+///
+/// giveup::config()
+///     .exit_code_map(ExitCodeMap::sysexits().error::<std::num::ParseIntError>(EX_CONFIG))
+///     .apply();
+/// ```
+#[derive(Clone, Default)]
+pub struct ExitCodeMap {
+	rules: Vec<Rule>,
+}
+
+impl ExitCodeMap {
+	/// Create a map without any rules.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Map `io::Error`s and parse errors of the standard
+	/// library onto [`sysexits`] codes.
+	///
+	/// - `NotFound` exits with `EX_NOINPUT`
+	/// - `PermissionDenied` exits with `EX_NOPERM`
+	/// - `AlreadyExists` exits with `EX_CANTCREAT`
+	/// - `ConnectionRefused`, `ConnectionReset` and `NotConnected` exit with `EX_UNAVAILABLE`
+	/// - `TimedOut`, `Interrupted` and `WouldBlock` exit with `EX_TEMPFAIL`
+	/// - `InvalidData` and errors parsing numbers, booleans,
+	///   addresses and UTF-8 exit with `EX_DATAERR`
+	/// - Any other `io::Error` exits with `EX_IOERR`
+	pub fn sysexits() -> Self {
+		use self::sysexits::*;
+		Self::new()
+			.io_kind(ErrorKind::NotFound, EX_NOINPUT)
+			.io_kind(ErrorKind::PermissionDenied, EX_NOPERM)
+			.io_kind(ErrorKind::AlreadyExists, EX_CANTCREAT)
+			.io_kind(ErrorKind::ConnectionRefused, EX_UNAVAILABLE)
+			.io_kind(ErrorKind::ConnectionReset, EX_UNAVAILABLE)
+			.io_kind(ErrorKind::NotConnected, EX_UNAVAILABLE)
+			.io_kind(ErrorKind::TimedOut, EX_TEMPFAIL)
+			.io_kind(ErrorKind::Interrupted, EX_TEMPFAIL)
+			.io_kind(ErrorKind::WouldBlock, EX_TEMPFAIL)
+			.io_kind(ErrorKind::InvalidData, EX_DATAERR)
+			.error::<io::Error>(EX_IOERR)
+			.error::<std::num::ParseIntError>(EX_DATAERR)
+			.error::<std::num::ParseFloatError>(EX_DATAERR)
+			.error::<std::str::ParseBoolError>(EX_DATAERR)
+			.error::<std::net::AddrParseError>(EX_DATAERR)
+			.error::<std::str::Utf8Error>(EX_DATAERR)
+			.error::<std::string::FromUtf8Error>(EX_DATAERR)
+	}
+
+	/// Exit with `code` if an error is an `io::Error` of the given kind.
+	pub fn io_kind(mut self, kind: ErrorKind, code: i32) -> Self {
+		self.rules.push(Rule::IoKind(kind, code));
+		self
+	}
+
+	/// Exit with `code` if an error is of type `E`.
+	pub fn error<E>(self, code: i32) -> Self
+	where
+		E: Error + 'static,
+	{
+		self.classify(move |e| e.is::<E>().then_some(code))
+	}
+
+	/// Exit with the code returned by `classify` if it returns one,
+	/// e.g. to pick the code based on the variant of an error.
+	pub fn classify<F>(mut self, classify: F) -> Self
+	where
+		F: Fn(&(dyn Error + 'static)) -> Option<i32> + Send + Sync + 'static,
+	{
+		self.rules.push(Rule::Classify(Arc::new(classify)));
+		self
+	}
+
+	// The code picked for the chain of errors by the first matching rule.
+	fn code_for(&self, chain: &[&(dyn Error + 'static)]) -> Option<i32> {
+		chain.iter().find_map(|e| self.rules.iter().find_map(|rule| rule.code_for(*e)))
+	}
+}
+
+impl Rule {
+	fn code_for(&self, e: &(dyn Error + 'static)) -> Option<i32> {
+		match self {
+			Rule::IoKind(kind, code) => e.downcast_ref::<io::Error>()
+				.filter(|e| e.kind() == *kind)
+				.map(|_| *code),
+			Rule::Classify(classify) => classify(e),
+		}
+	}
+}
+
+impl fmt::Debug for ExitCodeMap {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("ExitCodeMap")
+			.field("rules", &self.rules.len())
+			.finish()
+	}
+}
+
+// Exit code of an error, which is checked
+// against all rules along with its sources.
+pub(crate) fn error_exit_code(err: &(dyn Error + 'static)) -> i32 {
+	let config = current_config();
+	let Some(map) = &config.exit_code_map else {
+		return config.exit_code;
+	};
+	let mut chain = vec![err];
+	chain.extend(sources(err));
+	map.code_for(&chain).unwrap_or(config.exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::sysexits::*;
+
+	#[test]
+	fn outer_most_errors_are_checked_first() {
+		#[derive(Debug)]
+		struct ConfigError(io::Error);
+		impl Error for ConfigError {
+			fn source(&self) -> Option<&(dyn Error + 'static)> {
+				Some(&self.0)
+			}
+		}
+		impl fmt::Display for ConfigError {
+			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("invalid configuration")
+			}
+		}
+
+		let map = ExitCodeMap::sysexits();
+		let err = ConfigError(io::Error::new(ErrorKind::NotFound, "missing"));
+		assert_eq!(map.code_for(&[&err]), None);
+		assert_eq!(map.code_for(&[&err, &err.0]), Some(EX_NOINPUT));
+		let map = ExitCodeMap::new().error::<ConfigError>(EX_CONFIG).io_kind(ErrorKind::NotFound, EX_NOINPUT);
+		assert_eq!(map.code_for(&[&err, &err.0]), Some(EX_CONFIG));
+	}
+
+	#[test]
+	fn other_io_errors_are_io_errors() {
+		let map = ExitCodeMap::sysexits();
+		let err = io::Error::new(ErrorKind::Unsupported, "unsupported");
+		assert_eq!(map.code_for(&[&err]), Some(EX_IOERR));
+		let err = io::Error::new(ErrorKind::PermissionDenied, "denied");
+		assert_eq!(map.code_for(&[&err]), Some(EX_NOPERM));
+		assert_eq!(map.code_for(&[&"x".parse::<u8>().unwrap_err()]), Some(EX_DATAERR));
+	}
+}
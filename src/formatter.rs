@@ -0,0 +1,553 @@
+use crate::options::{FormatOptions, Verbosity};
+use crate::output::write_output;
+use crate::report::{more_causes, ErrorReport};
+use crate::theme::{Style, Theme};
+use std::fmt;
+use std::sync::Arc;
+
+/// Layout of the human-readable output.
+///
+/// Install a formatter using [`ConfigBuilder::formatter`](crate::ConfigBuilder::formatter)
+/// to lay out reports differently, without giving up the rest of the
+/// crate. Reports are still wrapped according to
+/// [`FormatOptions::wrap`] afterwards.
+/// # Example
+/// ```rust
+/// use giveup::{ErrorReport, FormatOptions, ReportFormatter, Theme};
+///
+/// // Display the error first and the message below it.
+/// struct ErrorFirst;
+///
+/// impl ReportFormatter for ErrorFirst {
+///     fn format(&self, report: &ErrorReport, _opts: &FormatOptions, theme: &Theme) -> String {
+///         let mut output = format!("{}\n", report.primary);
+///         output.push_str(&format!("  while: {}\n", theme.message.paint(&report.message)));
+///         for hint in &report.hints {
+///             output.push_str(&format!("  help: {}\n", theme.hint.paint(hint)));
+///         }
+///         output
+///     }
+/// }
+///
+/// giveup::config().formatter(ErrorFirst).apply();
+/// ```
+pub trait ReportFormatter: Send + Sync {
+	/// Format the whole output for `report`, ending in a newline.
+	///
+	/// `theme` is [`Theme::plain`] if the output isn't colored.
+	fn format(&self, report: &ErrorReport, opts: &FormatOptions, theme: &Theme) -> String;
+
+	/// Write the output of [`format`](Self::format) to `out`.
+	///
+	/// This is how reports are displayed. Override it to write long
+	/// reports piece by piece instead of collecting them first.
+	fn write(&self, out: &mut dyn fmt::Write, report: &ErrorReport, opts: &FormatOptions, theme: &Theme) -> fmt::Result {
+		out.write_str(&self.format(report, opts, theme))
+	}
+}
+
+/// The layout used unless another [`ReportFormatter`] is installed.
+///
+/// Custom formatters can delegate to it to only change parts of the output.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultFormatter;
+
+impl ReportFormatter for DefaultFormatter {
+	fn format(&self, report: &ErrorReport, opts: &FormatOptions, theme: &Theme) -> String {
+		let mut output = String::new();
+		// Writing to a `String` never fails.
+		let _ = self.write(&mut output, report, opts, theme);
+		output
+	}
+
+	fn write(&self, out: &mut dyn fmt::Write, report: &ErrorReport, opts: &FormatOptions, theme: &Theme) -> fmt::Result {
+		// Only the first line is needed to lay out the message.
+		// Everything below it is written as it is formatted.
+		let first_line = format!("{}\n", report.primary);
+		write_output(out, &report.message, report, &first_line, opts, theme)?;
+		report.write_details(out, opts, theme)
+	}
+}
+
+/// Formatter which lays out reports as Markdown, e.g. for
+/// tools which post errors to issue trackers or chats.
+///
+/// The message becomes a headline followed by the error, its causes as
+/// a list, the hints and the example commands as a fenced code block.
+/// Styling isn't applied. Lines should not be wrapped, so turn that off:
+/// ```rust
+/// use giveup::{MarkdownFormatter, Wrap};
+/// giveup::config()
+///     .formatter(MarkdownFormatter)
+///     .wrap(Wrap::Never)
+///     .apply();
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarkdownFormatter;
+
+impl ReportFormatter for MarkdownFormatter {
+	fn format(&self, report: &ErrorReport, opts: &FormatOptions, _theme: &Theme) -> String {
+		let mut sections = Vec::new();
+
+		let code = report.code.as_ref().map(|code| format!("`{code}` ")).unwrap_or_default();
+		let (headline, primary) = if report.message.is_empty() {
+			(&report.primary, None)
+		} else {
+			(&report.message, Some(&report.primary))
+		};
+		sections.push(format!("## {code}{headline}\n"));
+		sections.extend(primary.filter(|primary| !primary.is_empty()).map(|primary| format!("{primary}\n")));
+		if !report.items.is_empty() {
+			let items: Vec<String> = report.items.iter().map(ErrorReport::item_line).collect();
+			sections.push(items.iter().enumerate().map(|(idx, item)| format!("{}. {item}\n", idx + 1)).collect());
+		}
+
+		if opts.verbosity == Verbosity::Quiet {
+			return sections.join("\n");
+		}
+
+		let strings = &opts.strings;
+
+		let show_causes = opts.show_causes || opts.verbosity == Verbosity::Verbose;
+		if show_causes && !report.causes.is_empty() {
+			let (mut causes, hidden) = report.displayed_causes(opts);
+			if hidden > 0 {
+				causes.push(more_causes(hidden));
+			}
+			sections.push(format!("**{}**\n{}", strings.caused_by, markdown_list(&causes)));
+		}
+
+		if let Some(snippet) = &report.snippet {
+			sections.push(format!("```text\n{}\n```\n", snippet.trim_end()));
+		}
+
+		match report.hints.as_slice() {
+			[] => {},
+			[hint] => sections.push(format!("**Hint:** {hint}\n")),
+			hints => sections.push(format!("**Hints:**\n{}", markdown_list(hints))),
+		}
+
+		if !report.examples.is_empty() {
+			let mut block = String::from("```sh\n");
+			for example in &report.examples {
+				if let Some(label) = &example.label {
+					block.push_str(&format!("# {label}\n"));
+				}
+				block.push_str(&format!("{}\n", example.command));
+			}
+			block.push_str("```\n");
+			sections.push(block);
+		}
+
+		for note in &report.notes {
+			// Notes can span multiple lines (e.g. labels of diagnostics).
+			let quoted = note.lines().collect::<Vec<_>>().join("\n> ");
+			sections.push(format!("> **{}** {quoted}\n", strings.note));
+		}
+
+		if !report.links.is_empty() {
+			sections.push(format!("**{}**\n{}", strings.see_also, markdown_list(&report.links)));
+		}
+
+		if let Some(location) = &report.location {
+			sections.push(format!("{} `{location}`\n", strings.location));
+		}
+
+		if let Some(debug) = &report.debug {
+			sections.push(format!("**{}**\n```text\n{}\n```\n", strings.debug, debug.trim_end()));
+		}
+
+		if let Some(span_trace) = &report.span_trace {
+			sections.push(format!("**{}**\n```text\n{}\n```\n", strings.span_trace, span_trace.trim_end()));
+		}
+
+		if let Some(backtrace) = &report.backtrace {
+			sections.push(format!("**{}**\n```text\n{}\n```\n", strings.backtrace, backtrace.trim_end()));
+		}
+
+		sections.join("\n")
+	}
+}
+
+fn markdown_list(items: &[String]) -> String {
+	items.iter().map(|item| format!("- {item}\n")).collect()
+}
+
+/// Formatter which assembles reports from a template, so
+/// tools with a house style can match their existing output.
+///
+/// Parts of the report are inserted in place of these placeholders:
+///
+/// | Placeholder | Part of the report |
+/// | --- | --- |
+/// | `{title}` | The message passed to `giveup` |
+/// | `{code}` | The error's code |
+/// | `{error}` | The error |
+/// | `{item}` | Each of the errors reported together, e.g. by a [`MultiError`](crate::MultiError) |
+/// | `{cause}` | Each of the causes |
+/// | `{snippet}` | The source snippet |
+/// | `{hint}` | Each of the hints |
+/// | `{example}` | Each of the example commands |
+/// | `{note}` | Each of the notes |
+/// | `{link}` | Each of the links |
+/// | `{location}` | Where the error was given up on in verbose output |
+///
+/// A line with a placeholder for one of the lists is repeated for each
+/// item and left out if there are none. `{items}`, `{causes}`, `{hints}`,
+/// `{examples}`, `{notes}` and `{links}` are accepted as well. Lines
+/// whose placeholders are all empty are left out, too. Text at the start
+/// or end of a line is left out with the placeholder next to it if that
+/// one is empty. Text between two placeholders is only kept if the one in
+/// front of it isn't empty and something is displayed after it. Write
+/// `{{` and `}}` for literal braces. Unknown placeholders are kept as
+/// they are.
+///
+/// Like the default layout, the causes are only displayed if
+/// [`show_causes`](FormatOptions::show_causes) is on or the output is
+/// verbose, and quiet output only has the title, code and error.
+/// # Example
+/// ```rust
+/// use giveup::{ErrorReport, FormatOptions, ReportFormatter, TemplateFormatter, Theme};
+///
+/// let formatter = TemplateFormatter::new("{title}: {error}\n  because {cause}\nhelp: {hint}");
+/// let report = ErrorReport {
+///     message: "Missing configuration file".to_owned(),
+///     primary: "No such file or directory".to_owned(),
+///     hints: vec!["Create it".to_owned()],
+///     ..Default::default()
+/// };
+/// let output = formatter.format(&report, &FormatOptions::default(), &Theme::plain());
+/// assert_eq!(output, "Missing configuration file: No such file or directory\nhelp: Create it\n");
+///
+/// giveup::config().formatter(formatter).apply();
+/// ```
+#[derive(Debug, Clone)]
+pub struct TemplateFormatter {
+	lines: Vec<Vec<Piece>>,
+}
+
+#[derive(Debug, Clone)]
+enum Piece {
+	Text(String),
+	Field(Field),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Field {
+	Title,
+	Code,
+	Error,
+	Item,
+	Cause,
+	Snippet,
+	Hint,
+	Example,
+	Note,
+	Link,
+	Location,
+}
+
+impl Field {
+	fn from_name(name: &str) -> Option<Self> {
+		Some(match name {
+			"title" => Self::Title,
+			"code" => Self::Code,
+			"error" => Self::Error,
+			"item" | "items" => Self::Item,
+			"cause" | "causes" => Self::Cause,
+			"snippet" => Self::Snippet,
+			"hint" | "hints" => Self::Hint,
+			"example" | "examples" => Self::Example,
+			"note" | "notes" => Self::Note,
+			"link" | "links" => Self::Link,
+			"location" => Self::Location,
+			_ => return None,
+		})
+	}
+}
+
+impl TemplateFormatter {
+	/// Create a formatter which lays out reports according to `template`.
+	pub fn new(template: &str) -> Self {
+		let template = template.strip_suffix('\n').unwrap_or(template);
+		Self {
+			lines: template.split('\n').map(parse_template_line).collect(),
+		}
+	}
+}
+
+fn parse_template_line(line: &str) -> Vec<Piece> {
+	let mut pieces = Vec::new();
+	let mut text = String::new();
+	let mut rest = line;
+	while let Some(idx) = rest.find(['{', '}']) {
+		text.push_str(&rest[..idx]);
+		rest = &rest[idx..];
+		if let Some(after) = rest.strip_prefix("{{").or_else(|| rest.strip_prefix("}}")) {
+			text.push_str(&rest[..1]);
+			rest = after;
+			continue;
+		}
+		let field = rest.strip_prefix('{')
+			.and_then(|after| after.split_once('}'))
+			.and_then(|(name, after)| Some((Field::from_name(name)?, after)));
+		match field {
+			Some((field, after)) => {
+				if !text.is_empty() {
+					pieces.push(Piece::Text(std::mem::take(&mut text)));
+				}
+				pieces.push(Piece::Field(field));
+				rest = after;
+			},
+			None => {
+				text.push_str(&rest[..1]);
+				rest = &rest[1..];
+			},
+		}
+	}
+	text.push_str(rest);
+	if !text.is_empty() {
+		pieces.push(Piece::Text(text));
+	}
+	pieces
+}
+
+impl ReportFormatter for TemplateFormatter {
+	fn format(&self, report: &ErrorReport, opts: &FormatOptions, theme: &Theme) -> String {
+		let quiet = opts.verbosity == Verbosity::Quiet;
+		let show_causes = !quiet && (opts.show_causes || opts.verbosity == Verbosity::Verbose);
+		let causes = if show_causes {
+			let (mut causes, hidden) = report.displayed_causes(opts);
+			if hidden > 0 {
+				causes.push(more_causes(hidden));
+			}
+			causes
+		} else {
+			Vec::new()
+		};
+		let examples: Vec<String> = report.examples.iter().map(ToString::to_string).collect();
+		let items: Vec<String> = report.items.iter().map(ErrorReport::item_line).collect();
+		let list = |field| match field {
+			Field::Item | Field::Hint | Field::Example | Field::Note | Field::Link if quiet => Some(&[][..]),
+			Field::Item => Some(items.as_slice()),
+			Field::Cause => Some(causes.as_slice()),
+			Field::Hint => Some(report.hints.as_slice()),
+			Field::Example => Some(examples.as_slice()),
+			Field::Note => Some(report.notes.as_slice()),
+			Field::Link => Some(report.links.as_slice()),
+			_ => None,
+		};
+		let scalar = |field| match field {
+			Field::Title => report.message.as_str(),
+			Field::Code => report.code.as_deref().unwrap_or_default(),
+			Field::Error => report.primary.as_str(),
+			Field::Snippet | Field::Location if quiet => "",
+			Field::Snippet => report.snippet.as_deref().map_or("", str::trim_end),
+			Field::Location => report.location.as_deref().unwrap_or_default(),
+			_ => "",
+		};
+		let style = |field| match field {
+			Field::Title => theme.message,
+			Field::Code => theme.label,
+			Field::Hint => theme.hint,
+			Field::Example => theme.example,
+			Field::Note | Field::Location => theme.note,
+			_ => Style::new(),
+		};
+
+		let mut output = String::new();
+		for line in &self.lines {
+			let items = line.iter()
+				.filter_map(|piece| match piece {
+					Piece::Field(field) => list(*field).map(<[String]>::len),
+					Piece::Text(_) => None,
+				})
+				.max();
+			for idx in 0..items.unwrap_or(1) {
+				// The value of each placeholder, and `None` for text.
+				let values: Vec<Option<&str>> = line.iter()
+					.map(|piece| match piece {
+						Piece::Field(field) => Some(match list(*field) {
+							Some(items) => items.get(idx).map_or("", String::as_str),
+							None => scalar(*field),
+						}),
+						Piece::Text(_) => None,
+					})
+					.collect();
+				let fields: Vec<&str> = values.iter().flatten().copied().collect();
+				if !fields.is_empty() && fields.iter().all(|value| value.is_empty()) {
+					continue;
+				}
+				for (pos, piece) in line.iter().enumerate() {
+					match piece {
+						Piece::Text(text) => {
+							if keeps_text(&values, pos) {
+								output.push_str(text);
+							}
+						},
+						Piece::Field(field) => {
+							let value = values[pos].unwrap_or_default();
+							if !value.is_empty() {
+								// Writing to a `String` never fails.
+								let _ = style(*field).write(&mut output, value);
+							}
+						},
+					}
+				}
+				output.push('\n');
+			}
+		}
+		output
+	}
+}
+
+// Whether the text at `pos` in a line with the given placeholder
+// `values` is displayed, so separators of empty placeholders aren't.
+fn keeps_text(values: &[Option<&str>], pos: usize) -> bool {
+	let before = values[..pos].iter().rev().flatten().next();
+	let mut after = values[pos + 1..].iter().flatten().peekable();
+	match (before, after.peek()) {
+		(Some(before), Some(_)) => !before.is_empty() && after.any(|value| !value.is_empty()),
+		(Some(last), None) => !last.is_empty(),
+		(None, Some(first)) => !first.is_empty(),
+		(None, None) => true,
+	}
+}
+
+/// Formatter installed for the whole process.
+#[derive(Clone)]
+pub(crate) struct Formatter(Arc<dyn ReportFormatter>);
+
+impl Formatter {
+	pub(crate) fn new<F>(formatter: F) -> Self
+	where
+		F: ReportFormatter + 'static,
+	{
+		Self(Arc::new(formatter))
+	}
+}
+
+impl ReportFormatter for Formatter {
+	fn format(&self, report: &ErrorReport, opts: &FormatOptions, theme: &Theme) -> String {
+		self.0.format(report, opts, theme)
+	}
+
+	fn write(&self, out: &mut dyn fmt::Write, report: &ErrorReport, opts: &FormatOptions, theme: &Theme) -> fmt::Result {
+		self.0.write(out, report, opts, theme)
+	}
+}
+
+impl fmt::Debug for Formatter {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("Formatter(..)")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::report::ExampleCommand;
+
+	#[test]
+	fn markdown_contains_all_segments() {
+		let report = ErrorReport {
+			code: Some("E013".to_owned()),
+			primary: "permission denied".to_owned(),
+			causes: vec!["os error 13".to_owned()],
+			hints: vec!["Check the file's permissions".to_owned()],
+			examples: vec![ExampleCommand::new("chmod +r config.toml"), ExampleCommand::labeled("as root", "sudo app")],
+			notes: vec!["The file is owned by root".to_owned()],
+			links: vec!["https://example.com/permissions".to_owned()],
+			..ErrorReport::with_message("Failed to read config")
+		};
+		let markdown = MarkdownFormatter.format(&report, &FormatOptions::default(), &Theme::default());
+		assert_eq!(markdown, concat!(
+			"## `E013` Failed to read config\n\n",
+			"permission denied\n\n",
+			"**Caused by:**\n- os error 13\n\n",
+			"**Hint:** Check the file's permissions\n\n",
+			"```sh\nchmod +r config.toml\n# as root\nsudo app\n```\n\n",
+			"> **note:** The file is owned by root\n\n",
+			"**see also:**\n- https://example.com/permissions\n",
+		));
+	}
+
+	#[test]
+	fn markdown_without_a_message_uses_the_error_as_headline() {
+		let report = ErrorReport {
+			primary: "permission denied".to_owned(),
+			hints: vec!["First".to_owned(), "Second".to_owned()],
+			..ErrorReport::default()
+		};
+		let markdown = MarkdownFormatter.format(&report, &FormatOptions::default(), &Theme::plain());
+		assert_eq!(markdown, "## permission denied\n\n**Hints:**\n- First\n- Second\n");
+	}
+
+	#[test]
+	fn templates_repeat_lines_for_each_item() {
+		let template = TemplateFormatter::new("[{code}] {title}: {error}\n{snippet}\n- {hint}: {example}\n{{{unknown}}}\n");
+		let report = ErrorReport {
+			code: Some("E013".to_owned()),
+			primary: "permission denied".to_owned(),
+			hints: vec!["First".to_owned(), "Second".to_owned()],
+			examples: vec![ExampleCommand::new("chmod +r config.toml")],
+			..ErrorReport::with_message("Failed to read config")
+		};
+		let output = template.format(&report, &FormatOptions::default(), &Theme::plain());
+		assert_eq!(output, concat!(
+			"[E013] Failed to read config: permission denied\n",
+			"- First: `chmod +r config.toml`\n",
+			"- Second\n",
+			"{{unknown}}\n",
+		));
+		let multi = ErrorReport {
+			primary: "2 errors".to_owned(),
+			items: vec![
+				ErrorReport { primary: "invalid port".to_owned(), ..ErrorReport::with_message("a.toml") },
+				ErrorReport { primary: "invalid host".to_owned(), ..ErrorReport::default() },
+			],
+			..ErrorReport::with_message("Invalid configuration")
+		};
+		let output = TemplateFormatter::new("{title}: {error}\n  {items}").format(&multi, &FormatOptions::default(), &Theme::plain());
+		assert_eq!(output, "Invalid configuration: 2 errors\n  a.toml: invalid port\n  invalid host\n");
+		let styled = TemplateFormatter::new("{title}\nnote: {notes}").format(&report, &FormatOptions::default(), &Theme::standard());
+		assert_eq!(styled, "\x1b[1mFailed to read config\x1b[0m\n");
+	}
+
+	#[test]
+	fn templates_leave_out_the_separators_of_empty_placeholders() {
+		let template = TemplateFormatter::new("[{code}] {title}: {error}\nat {location}");
+		let format = |report: &ErrorReport| template.format(report, &FormatOptions::default(), &Theme::plain());
+		let report = ErrorReport {
+			primary: "permission denied".to_owned(),
+			..ErrorReport::with_message("Failed to read config")
+		};
+		assert_eq!(format(&report), "Failed to read config: permission denied\n");
+		let report = ErrorReport {
+			code: Some("E013".to_owned()),
+			primary: "permission denied".to_owned(),
+			..ErrorReport::default()
+		};
+		assert_eq!(format(&report), "[E013] permission denied\n");
+	}
+
+	#[test]
+	fn templates_follow_the_format_options() {
+		let template = TemplateFormatter::new("{title}: {error}\n  because {cause}\nhelp: {hint}");
+		let report = ErrorReport {
+			primary: "permission denied".to_owned(),
+			causes: vec!["os error 13".to_owned()],
+			hints: vec!["Check the file's permissions".to_owned()],
+			..ErrorReport::with_message("Failed to read config")
+		};
+		let opts = FormatOptions { show_causes: false, ..FormatOptions::default() };
+		assert_eq!(
+			template.format(&report, &opts, &Theme::plain()),
+			"Failed to read config: permission denied\nhelp: Check the file's permissions\n",
+		);
+		let opts = FormatOptions { verbosity: Verbosity::Verbose, ..opts };
+		assert!(template.format(&report, &opts, &Theme::plain()).contains("  because os error 13\n"));
+		let opts = FormatOptions { verbosity: Verbosity::Quiet, ..FormatOptions::default() };
+		assert_eq!(template.format(&report, &opts, &Theme::plain()), "Failed to read config: permission denied\n");
+	}
+}
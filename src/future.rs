@@ -0,0 +1,92 @@
+use crate::giveup::{Giveup, GiveupFormatError};
+use crate::hint::HintedError;
+use std::fmt::Display;
+use std::future::Future;
+
+/// User-geared program termination for futures of `Result`s,
+/// so async code reads the same as the sync version.
+///
+/// It doesn't depend on an async runtime, so it works with any of them.
+/// # Example
+/// ```rust
+/// use giveup::{Giveup, GiveupFuture};
+/// async fn fetch() -> Result<String, std::io::Error> {
+///     Ok(String::new())
+/// }
+///
+/// async fn run() {
+///     let page = fetch().await_giveup("Download failed").await;
+///     let page = fetch()
+///         .hint_async("Check your network connection")
+///         .await
+///         .giveup("Download failed");
+/// }
+/// ```
+pub trait GiveupFuture<T, E>: Future<Output = Result<T, E>> + Sized
+where
+	E: GiveupFormatError,
+{
+	/// Wait for the result and return its value, or [`giveup`](Giveup::giveup)
+	/// on the error.
+	fn await_giveup<M>(self, msg: M) -> impl Future<Output = T>
+	where
+		M: Display;
+
+	/// Wait for the result and add a [`hint`](Giveup::hint) to the error.
+	fn hint_async<S>(self, hint: S) -> impl Future<Output = Result<T, HintedError<E>>>
+	where
+		S: Display;
+}
+
+impl<F, T, E> GiveupFuture<T, E> for F
+where
+	F: Future<Output = Result<T, E>>,
+	E: GiveupFormatError,
+{
+	fn await_giveup<M>(self, msg: M) -> impl Future<Output = T>
+	where
+		M: Display,
+	{
+		// The message is converted right away, so it doesn't
+		// have to outlive the future.
+		let msg = msg.to_string();
+		async move { self.await.giveup(msg) }
+	}
+
+	fn hint_async<S>(self, hint: S) -> impl Future<Output = Result<T, HintedError<E>>>
+	where
+		S: Display,
+	{
+		// The hint is converted right away, like it is by `hint`.
+		let hint = hint.to_string();
+		async move { self.await.hint(hint) }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::pin::pin;
+	use std::task::{Context, Poll, Waker};
+
+	// The futures in these tests are ready right away.
+	fn block_on<F: Future>(future: F) -> F::Output {
+		let mut cx = Context::from_waker(Waker::noop());
+		match pin!(future).poll(&mut cx) {
+			Poll::Ready(output) => output,
+			Poll::Pending => panic!("future isn't ready"),
+		}
+	}
+
+	#[test]
+	fn awaited_results_are_unwrapped() {
+		let value = block_on(async { "42".parse::<u8>() }.await_giveup("Invalid answer"));
+		assert_eq!(value, 42);
+	}
+
+	#[test]
+	fn awaited_errors_are_hinted() {
+		let err = block_on(async { "x".parse::<u8>() }.hint_async("Pass a number")).unwrap_err();
+		assert_eq!(err.format_err_msg(), "invalid digit found in string\nPass a number\n");
+	}
+}
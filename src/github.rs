@@ -0,0 +1,67 @@
+//! Workflow commands which make errors show up as
+//! annotations in GitHub Actions runs.
+
+use crate::options::FormatOptions;
+use crate::report::{ErrorReport, Severity};
+use std::io::Write;
+
+// `::error title=<message>::<error>` command for the report,
+// or `::warning` for warnings.
+pub(crate) fn annotation(report: &ErrorReport, opts: &FormatOptions) -> String {
+	let (command, label) = match report.severity {
+		Severity::Warning => ("warning", &opts.strings.warning),
+		Severity::Error | Severity::Fatal => ("error", &opts.strings.error),
+	};
+	let title = match (&report.code, report.message.is_empty()) {
+		(Some(code), false) => format!("{label}[{code}]: {}", report.message),
+		(Some(code), true) => format!("{label}[{code}]"),
+		(None, false) => report.message.clone(),
+		(None, true) => label.to_string(),
+	};
+	let err_msg = report.format_err_msg(opts);
+	format!("::{command} title={}::{}\n", escape_property(&title), escape_data(err_msg.trim_end()))
+}
+
+// Workflow commands are read from stdout.
+pub(crate) fn write_annotation(report: &ErrorReport, opts: &FormatOptions) {
+	let mut stdout = std::io::stdout().lock();
+	// There is nowhere left to report a failed write to.
+	let _ = stdout.write_all(annotation(report, opts).as_bytes());
+	let _ = stdout.flush();
+}
+
+fn escape_data(s: &str) -> String {
+	s.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+fn escape_property(s: &str) -> String {
+	escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn annotations_are_escaped() {
+		let report = ErrorReport {
+			primary: "100% wrong".to_owned(),
+			hints: vec!["Try again".to_owned()],
+			..ErrorReport::with_message("Failed to read a, b")
+		};
+		let opts = FormatOptions::default();
+		assert_eq!(annotation(&report, &opts), "::error title=Failed to read a%2C b::100%25 wrong%0ATry again\n");
+
+		let report = ErrorReport {
+			code: Some("E012".to_owned()),
+			..ErrorReport::with_message("message")
+		};
+		assert_eq!(annotation(&report, &opts), "::error title=error[E012]%3A message::\n");
+
+		let report = ErrorReport {
+			severity: Severity::Warning,
+			..ErrorReport::with_message("message")
+		};
+		assert_eq!(annotation(&report, &opts), "::warning title=message::\n");
+	}
+}
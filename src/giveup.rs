@@ -1,141 +1,725 @@
-use crate::hint::HintedError;
-use colored::Colorize;
+use crate::config::{current_config, default_exit_code, effective_config};
+use crate::exit_code_map::error_exit_code;
+use crate::hint::{Example, HintedError};
+use crate::io_hint::io_hint;
+use crate::library::Report;
+use crate::options::{format_options, FormatOptions, Verbosity};
+use crate::output::{exit_gracefully, format_report, print_report};
+use crate::parse_error::{parse_error, parse_hint, source_parse_error};
+use crate::report::{backtrace_string, format_segments, ErrorReport, Severity};
+use std::fmt::Display;
+use std::panic::Location;
 
 /// User-geared program termination.
+// The example is indented with tabs like the rest of the code.
+#[allow(clippy::tabs_in_doc_comments)]
 pub trait Giveup<T, E>
 where
 	E: GiveupFormatError,
 {
 	/// Terminate the program gracefully and display a user-geared
 	/// error message.
+	///
+	/// The message can be anything implementing `Display`, e.g. a
+	/// `String`, `path.display()` or `format_args!(...)`.
 	/// # Example
 	/// ```rust
 	/// use std::io;
 	/// use giveup::Giveup;
 	/// fn main() {
 	/// 	let mut input = String::new();
-	/// # // cfg_if is used to get the doc test passing.
-	/// # cfg_if::cfg_if! {
-	/// 	# if #[cfg(feature = "anyhow")] {
-	/// 	# } else {
 	/// 	io::stdin().read_line(&mut input)
 	/// 	// Instead of panicing a user-geared error message is displayed
 	/// 		.giveup("Failed to read input");
-	///		# }
-	/// # }
 	/// }
 	/// ```
-	fn giveup(self, msg: &str) -> T;
+	fn giveup<M>(self, msg: M) -> T
+	where
+		M: Display;
+	/// Same as [`giveup`](Giveup::giveup), but the message is only
+	/// constructed if the program is actually terminated.
+	///
+	/// This avoids building dynamic messages on the happy path.
+	/// # Example
+	/// ```rust
+	/// use giveup::Giveup;
+	/// let path = "config-path";
+	/// let port: u16 = "8080".parse()
+	///     .giveup_with(|| format!("Invalid port in {path}"));
+	/// ```
+	fn giveup_with<F, M>(self, msg: F) -> T
+	where
+		F: FnOnce() -> M,
+		M: Display;
+	/// Same as [`giveup`](Giveup::giveup), but the program exits
+	/// with the given `code` instead of the error's
+	/// [`exit_code`](GiveupExitCode::exit_code).
+	/// # Example
+	/// ```rust
+	/// use giveup::Giveup;
+	/// // Exit with `EX_USAGE` if the argument is invalid.
+	/// let count: u32 = "3".parse().giveup_code("Invalid count", 64);
+	/// ```
+	fn giveup_code<M>(self, msg: M, code: i32) -> T
+	where
+		M: Display;
+	/// Format the error exactly like [`giveup`](Giveup::giveup) would
+	/// display it, but return it instead of terminating the program.
+	///
+	/// The returned string isn't styled, so it can be logged as it is.
+	/// This is meant for long-running programs like daemons, which
+	/// must keep running after an error.
+	/// # Example
+	/// ```rust
+	/// use giveup::Giveup;
+	/// let err = "x".parse::<u32>().report("Invalid count").unwrap_err();
+	/// assert!(err.starts_with("Invalid count: invalid digit found in string\n"));
+	/// ```
+	fn report<M>(self, msg: M) -> Result<T, String>
+	where
+		M: Display;
+	/// Compose the report like [`giveup`](Giveup::giveup) would, but
+	/// return it as an error instead of terminating the program.
+	///
+	/// This lets library crates use the same hints and examples
+	/// while the binary decides when to give up. See [`Report`].
+	/// # Example
+	/// ```rust
+	/// use giveup::Giveup;
+	/// let err = "x".parse::<u32>().hint("Pass a number").giveup_err("Invalid count").unwrap_err();
+	/// assert_eq!(err.report().message, "Invalid count");
+	/// assert_eq!(err.report().hints, vec!["Pass a number"]);
+	/// ```
+	fn giveup_err<M>(self, msg: M) -> Result<T, Report>
+	where
+		M: Display;
+	/// Display the error like [`giveup`](Giveup::giveup) would, but
+	/// return `None` instead of terminating the program.
+	///
+	/// This is meant for failures the program can recover from, e.g.
+	/// to skip a file and tell the user why.
+	/// # Example
+	/// ```rust
+	/// use giveup::Giveup;
+	/// for path in ["Cargo.toml", "missing.toml"] {
+	///     let Some(contents) = std::fs::read_to_string(path)
+	///         .hint("Skipping this file")
+	///         .complain(format_args!("Failed to read {path}"))
+	///     else {
+	///         continue;
+	///     };
+	///     println!("{path} has {} bytes", contents.len());
+	/// }
+	/// ```
+	fn complain<M>(self, msg: M) -> Option<T>
+	where
+		M: Display;
+	/// Same as [`complain`](Giveup::complain), but the error is
+	/// displayed as a warning with a (yellow) `warning:` label.
+	///
+	/// This is meant for problems the program works around, so
+	/// warnings and errors are displayed consistently.
+	/// # Example
+	/// ```rust
+	/// use giveup::Giveup;
+	/// let theme = std::env::var("APP_THEME")
+	///     .hint("Using the default theme")
+	///     .warn("APP_THEME isn't set")
+	///     .unwrap_or_else(|| String::from("light"));
+	/// ```
+	fn warn<M>(self, msg: M) -> Option<T>
+	where
+		M: Display;
+	/// Display the error like [`giveup`](Giveup::giveup) would, but
+	/// continue with `default` instead of terminating the program.
+	///
+	/// A note tells users that the program continues with a default
+	/// value, so tools can degrade gracefully while keeping them informed.
+	/// # Example
+	/// ```rust
+	/// use giveup::Giveup;
+	/// let config = std::fs::read_to_string("missing.toml")
+	///     .hint("Create a configuration file")
+	///     .giveup_or(String::from("port = 8080"), "Failed to read the configuration");
+	/// assert_eq!(config, "port = 8080");
+	/// ```
+	fn giveup_or<M>(self, default: T, msg: M) -> T
+	where
+		M: Display;
+	/// Same as [`giveup_or`](Giveup::giveup_or), but the program
+	/// continues with `T::default()`.
+	fn giveup_or_default<M>(self, msg: M) -> T
+	where
+		T: Default,
+		M: Display;
 	/// Add hints to errors to help users solve the issue which
 	/// raised the error.
 	///
 	/// [`example`](crate::hint::Example::example) can be called on
 	/// `Result`s returned by this method to subsidize the hint with
 	/// an example of the recommended action.
-	fn hint(self, hint: &str) -> Result<T, HintedError<E>>;
+	///
+	/// The hint can be anything implementing `Display`, so it can be
+	/// built from runtime data, too, e.g. using `format_args!(...)`.
+	/// Calling `hint` again on the returned `Result` adds another
+	/// hint and all of them are displayed as a list.
+	fn hint<S>(self, hint: S) -> Result<T, HintedError<E>>
+	where
+		S: Display;
+
+	/// Same as [`hint`](Self::hint), but the hint is only
+	/// computed if `self` is an error.
+	///
+	/// This avoids looking up what the hint needs (e.g. paths or
+	/// environment variables) on the happy path.
+	/// # Example
+	/// ```rust
+	/// use giveup::Giveup;
+	/// let config = std::fs::read_to_string("Cargo.toml")
+	///     .hint_with(|| format!("Create {}", std::env::current_dir().unwrap().join("Cargo.toml").display()))
+	///     .giveup("Failed to read the configuration");
+	/// ```
+	fn hint_with<F, S>(self, hint: F) -> Result<T, HintedError<E>>
+	where
+		F: FnOnce() -> S,
+		S: Display;
+
+	/// Same as [`hint`](Self::hint), but the hint is only
+	/// displayed if the program is built for Unix.
+	///
+	/// Together with [`hint_windows`](Self::hint_windows) a single
+	/// call site can tell users what to do on their platform.
+	/// # Example
+	/// ```rust
+	/// use giveup::Giveup;
+	/// let config = std::fs::read_to_string("Cargo.toml")
+	///     .hint_unix("Run `chmod +r Cargo.toml` to make it readable")
+	///     .hint_windows("Run `icacls Cargo.toml /grant %USERNAME%:R` to make it readable")
+	///     .giveup("Failed to read the configuration");
+	/// ```
+	fn hint_unix<S>(self, hint: S) -> Result<T, HintedError<E>>
+	where
+		S: Display;
+
+	/// Same as [`hint`](Self::hint), but the hint is only
+	/// displayed if the program is built for Windows.
+	fn hint_windows<S>(self, hint: S) -> Result<T, HintedError<E>>
+	where
+		S: Display;
+
+	/// Add an example command to errors without a hint sentence.
+	///
+	/// This is the same as [`example`](crate::hint::Example::example),
+	/// which only exists on `Result`s returned by [`hint`](Self::hint).
+	/// It has its own name because a second `example` method on every
+	/// `Result` would be ambiguous with the one of `Example`.
+	/// # Example
+	/// ```rust
+	/// use giveup::Giveup;
+	/// let count: u32 = "3".parse()
+	///     .with_example("app --count 3")
+	///     .giveup("Invalid count");
+	/// ```
+	fn with_example<S>(self, example: S) -> Result<T, HintedError<E>>
+	where
+		S: Display;
 }
 
 impl<T, E> Giveup<T, E> for Result<T, E>
 where
 	E: GiveupFormatError,
 {
-	fn giveup(self, msg: &str) -> T {
+	#[track_caller]
+	fn giveup<M>(self, msg: M) -> T
+	where
+		M: Display,
+	{
+		self.giveup_with(|| msg.to_string())
+	}
+
+	#[track_caller]
+	fn giveup_with<F, M>(self, msg: F) -> T
+	where
+		F: FnOnce() -> M,
+		M: Display,
+	{
 		match self {
 			Ok(t) => t,
+			Err(e) => exit_gracefully(&giveup_report(&e, &msg().to_string()), e.exit_code()),
+		}
+	}
+
+	#[track_caller]
+	fn giveup_code<M>(self, msg: M, code: i32) -> T
+	where
+		M: Display,
+	{
+		match self {
+			Ok(t) => t,
+			Err(e) => exit_gracefully(&giveup_report(&e, &msg.to_string()), code),
+		}
+	}
+
+	#[track_caller]
+	fn report<M>(self, msg: M) -> Result<T, String>
+	where
+		M: Display,
+	{
+		// Closures can't track their caller, so the
+		// results are matched on instead.
+		match self {
+			Ok(t) => Ok(t),
+			Err(e) => Err(format_report(&giveup_report(&e, &msg.to_string()), &effective_config(), false)),
+		}
+	}
+
+	#[track_caller]
+	fn giveup_err<M>(self, msg: M) -> Result<T, Report>
+	where
+		M: Display,
+	{
+		match self {
+			Ok(t) => Ok(t),
+			Err(e) => Err(Report::new(giveup_report(&e, &msg.to_string()), e.exit_code())),
+		}
+	}
+
+	#[track_caller]
+	fn complain<M>(self, msg: M) -> Option<T>
+	where
+		M: Display,
+	{
+		match self {
+			Ok(t) => Some(t),
 			Err(e) => {
-				let err_msg = e.format_err_msg();
-				exit_gracefully(msg, &err_msg)
-			}
+				print_report(&recovered_report(&e, &msg.to_string(), Severity::Error));
+				None
+			},
+		}
+	}
+
+	#[track_caller]
+	fn warn<M>(self, msg: M) -> Option<T>
+	where
+		M: Display,
+	{
+		match self {
+			Ok(t) => Some(t),
+			Err(e) => {
+				print_report(&recovered_report(&e, &msg.to_string(), Severity::Warning));
+				None
+			},
+		}
+	}
+
+	#[track_caller]
+	fn giveup_or<M>(self, default: T, msg: M) -> T
+	where
+		M: Display,
+	{
+		match self {
+			Ok(t) => t,
+			Err(e) => fall_back(&e, &msg.to_string(), default),
+		}
+	}
+
+	#[track_caller]
+	fn giveup_or_default<M>(self, msg: M) -> T
+	where
+		T: Default,
+		M: Display,
+	{
+		match self {
+			Ok(t) => t,
+			Err(e) => fall_back(&e, &msg.to_string(), T::default()),
 		}
 	}
 
-	fn hint(self, hint: &str) -> Result<T, HintedError<E>> {
+	#[track_caller]
+	fn hint<S>(self, hint: S) -> Result<T, HintedError<E>>
+	where
+		S: Display,
+	{
 		match self {
 			Ok(t) => Ok(t),
 			Err(e) => Err(HintedError::with_hint(e, hint)),
 		}
 	}
+
+	#[track_caller]
+	fn hint_with<F, S>(self, hint: F) -> Result<T, HintedError<E>>
+	where
+		F: FnOnce() -> S,
+		S: Display,
+	{
+		match self {
+			Ok(t) => Ok(t),
+			Err(e) => Err(HintedError::with_hint(e, hint())),
+		}
+	}
+
+	#[track_caller]
+	fn hint_unix<S>(self, hint: S) -> Result<T, HintedError<E>>
+	where
+		S: Display,
+	{
+		if cfg!(unix) {
+			self.hint(hint)
+		} else {
+			without_hint(self)
+		}
+	}
+
+	#[track_caller]
+	fn hint_windows<S>(self, hint: S) -> Result<T, HintedError<E>>
+	where
+		S: Display,
+	{
+		if cfg!(windows) {
+			self.hint(hint)
+		} else {
+			without_hint(self)
+		}
+	}
+
+	#[track_caller]
+	fn with_example<S>(self, example: S) -> Result<T, HintedError<E>>
+	where
+		S: Display,
+	{
+		without_hint(self).example(example)
+	}
+}
+
+impl<T> Giveup<T, NoneError> for Option<T> {
+	#[track_caller]
+	fn giveup<M>(self, msg: M) -> T
+	where
+		M: Display,
+	{
+		self.giveup_with(|| msg.to_string())
+	}
+
+	#[track_caller]
+	fn giveup_with<F, M>(self, msg: F) -> T
+	where
+		F: FnOnce() -> M,
+		M: Display,
+	{
+		match self {
+			Some(t) => t,
+			None => exit_gracefully(&giveup_report(&NoneError, &msg().to_string()), NoneError.exit_code()),
+		}
+	}
+
+	#[track_caller]
+	fn giveup_code<M>(self, msg: M, code: i32) -> T
+	where
+		M: Display,
+	{
+		match self {
+			Some(t) => t,
+			None => exit_gracefully(&giveup_report(&NoneError, &msg.to_string()), code),
+		}
+	}
+
+	#[track_caller]
+	fn report<M>(self, msg: M) -> Result<T, String>
+	where
+		M: Display,
+	{
+		self.ok_or(NoneError).report(msg)
+	}
+
+	#[track_caller]
+	fn giveup_err<M>(self, msg: M) -> Result<T, Report>
+	where
+		M: Display,
+	{
+		self.ok_or(NoneError).giveup_err(msg)
+	}
+
+	#[track_caller]
+	fn complain<M>(self, msg: M) -> Option<T>
+	where
+		M: Display,
+	{
+		self.ok_or(NoneError).complain(msg)
+	}
+
+	#[track_caller]
+	fn warn<M>(self, msg: M) -> Option<T>
+	where
+		M: Display,
+	{
+		self.ok_or(NoneError).warn(msg)
+	}
+
+	#[track_caller]
+	fn giveup_or<M>(self, default: T, msg: M) -> T
+	where
+		M: Display,
+	{
+		self.ok_or(NoneError).giveup_or(default, msg)
+	}
+
+	#[track_caller]
+	fn giveup_or_default<M>(self, msg: M) -> T
+	where
+		T: Default,
+		M: Display,
+	{
+		self.ok_or(NoneError).giveup_or_default(msg)
+	}
+
+	#[track_caller]
+	fn hint<S>(self, hint: S) -> Result<T, HintedError<NoneError>>
+	where
+		S: Display,
+	{
+		self.ok_or(NoneError).hint(hint)
+	}
+
+	#[track_caller]
+	fn hint_with<F, S>(self, hint: F) -> Result<T, HintedError<NoneError>>
+	where
+		F: FnOnce() -> S,
+		S: Display,
+	{
+		self.ok_or(NoneError).hint_with(hint)
+	}
+
+	#[track_caller]
+	fn hint_unix<S>(self, hint: S) -> Result<T, HintedError<NoneError>>
+	where
+		S: Display,
+	{
+		self.ok_or(NoneError).hint_unix(hint)
+	}
+
+	#[track_caller]
+	fn hint_windows<S>(self, hint: S) -> Result<T, HintedError<NoneError>>
+	where
+		S: Display,
+	{
+		self.ok_or(NoneError).hint_windows(hint)
+	}
+
+	#[track_caller]
+	fn with_example<S>(self, example: S) -> Result<T, HintedError<NoneError>>
+	where
+		S: Display,
+	{
+		self.ok_or(NoneError).with_example(example)
+	}
+}
+
+// Wrap the error without a hint. Unlike `map_err`,
+// this keeps track of the caller.
+#[track_caller]
+fn without_hint<T, E>(result: Result<T, E>) -> Result<T, HintedError<E>> {
+	match result {
+		Ok(t) => Ok(t),
+		Err(e) => Err(HintedError::new(e)),
+	}
 }
 
-fn exit_gracefully<S>(msg: S, err_msg: S) -> !
+// Report displayed when giving up on `e`. If the error didn't
+// bring its own backtrace or location, those of the caller are used.
+#[track_caller]
+pub(crate) fn giveup_report<E>(e: &E, msg: &str) -> ErrorReport
 where
-	S: AsRef<str>
+	E: GiveupFormatError,
+{
+	with_caller_context(format_segments(e, msg))
+}
+
+// Fill in the location, span trace and backtrace of the
+// caller where the report doesn't have its own.
+#[track_caller]
+pub(crate) fn with_caller_context(report: ErrorReport) -> ErrorReport {
+	let caller = Location::caller();
+	ErrorReport {
+		location: report.location.or_else(|| verbose_location(caller, &format_options())),
+		#[cfg(feature = "tracing")]
+		span_trace: report.span_trace.or_else(crate::trace::verbose_span_trace),
+		backtrace: report.backtrace.or_else(|| backtrace_string(&current_config().capture_backtrace())),
+		..report
+	}
+}
+
+// Report displayed for `e` if the program doesn't give up on it.
+#[track_caller]
+fn recovered_report<E>(e: &E, msg: &str, severity: Severity) -> ErrorReport
+where
+	E: GiveupFormatError,
+{
+	ErrorReport {
+		severity,
+		..giveup_report(e, msg)
+	}
+}
+
+// Note displayed by `giveup_or` and `giveup_or_default`.
+const FALLBACK_NOTE: &str = "Continuing with a default value";
+
+// Display the report of `e` and continue with `default`.
+#[track_caller]
+fn fall_back<E, T>(e: &E, msg: &str, default: T) -> T
+where
+	E: GiveupFormatError,
 {
-	let msg: &str = msg.as_ref();
-	let err_msg: &str = err_msg.as_ref();
-	// err_msg contains a trailing newline so and 
-	// additional newline is omitted here.
-	eprint!("{}: {}", msg.bold(), err_msg);
-	std::process::exit(1);
+	let mut report = recovered_report(e, msg, Severity::Error);
+	report.notes.push(FALLBACK_NOTE.to_owned());
+	print_report(&report);
+	default
 }
 
+/// Error used when giving up on an `Option` which is `None`.
+///
+/// It doesn't have a message of its own, so only the message
+/// passed to `giveup` and possibly a hint are displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoneError;
+
+impl GiveupExitCode for NoneError {}
+
+impl GiveupFormatError for NoneError {
+	fn segments(&self, _opts: &FormatOptions) -> ErrorReport {
+		ErrorReport::default()
+	}
+}
+
+/// Exit code used when giving up because of an error.
+///
+/// All errors implementing [`std::error::Error`] exit with the
+/// default exit code, which is 1 unless it's changed using
+/// [`config`](crate::config), or with the code picked by the
+/// [`ExitCodeMap`](crate::ExitCodeMap). Other error types can pick
+/// their own code by overriding [`exit_code`](GiveupExitCode::exit_code).
+pub trait GiveupExitCode {
+	/// The code the program exits with if it gives up
+	/// because of this error.
+	fn exit_code(&self) -> i32 {
+		default_exit_code()
+	}
+}
 
 // Any error which can be formatted by this crate.
-pub trait GiveupFormatError: Send + Sync {
+//
+// `giveup` never sends the error to another thread, so errors
+// which are not thread-safe (`!Send`/`!Sync`) are fine, too.
+pub trait GiveupFormatError: GiveupExitCode {
 	/// Format an error to display its contents to a CLI user.
-	fn format_err_msg(&self) -> String;
-}
-
-cfg_if::cfg_if! {
-	if #[cfg(feature = "anyhow")] {
-		impl GiveupFormatError for anyhow::Error {
-			fn format_err_msg(&self) -> String {
-				// The Display implementation of an `anyhow::Error` matches
-				// the one of the outer most contained error.
-				let mut err_msg = format!("{self}\n");
-
-				// `anyhow::Error::chain` is the same as manually going back
-				// through all the error's sources.
-				let mut cause_chain = self.chain();
-				cause_chain.next();  // Skip duplicate error.
-				for cause in cause_chain {
-					let cause_msg = format!("Caused by: {cause}\n");
-					err_msg.push_str(&cause_msg);
-				}
+	fn format_err_msg(&self) -> String {
+		let opts = format_options();
+		self.segments(&opts).format_err_msg(&opts)
+	}
 
-				err_msg
-			}
-		}
+	/// Split an error into the segments of its message according
+	/// to the given options.
+	///
+	/// The `message` of the returned report is left empty because
+	/// it is only known once `giveup` is called.
+	fn segments(&self, opts: &FormatOptions) -> ErrorReport;
+}
+
+impl<T> GiveupFormatError for T
+where
+	T: std::error::Error + 'static,
+{
+	fn segments(&self, opts: &FormatOptions) -> ErrorReport {
+		error_segments(self, opts)
+	}
+}
+
+impl<T> GiveupExitCode for T
+where
+	T: std::error::Error + 'static,
+{
+	fn exit_code(&self) -> i32 {
+		error_exit_code(self)
+	}
+}
+
+pub(crate) fn error_segments(
+	err:  &dyn std::error::Error,
+	opts: &FormatOptions,
+) -> ErrorReport {
+	// Error message starts with the Display implementation
+	// unless Debug is preferred.
+	let primary = if opts.prefer_debug {
+		format!("{err:?}")
 	} else {
-		impl<T> GiveupFormatError for T
-		where
-			T: std::error::Error + Send + Sync,
-		{
-			fn format_err_msg(&self) -> String {
-				// The logic behind the formatting lives outside of the implementation
-				// so it is still accessable even if this implementation is not compiled
-				// (i.e. if the anyhow features is enabled). This is required in testing.
-				format_err_msg(self)
-			}
-		}
+		parse_error(err).map_or_else(|| err.to_string(), |parse_error| parse_error.flat_message())
+	};
+
+	let hints = automatic_hints(err);
+	ErrorReport {
+		primary,
+		causes: cause_messages(err),
+		auto_hints: hints.len(),
+		hints,
+		debug: verbose_debug(err, opts),
+		..Default::default()
 	}
 }
 
-// In case the anyhow feature is enabled, this function is required
-// for testing purposes only.
-#[cfg_attr(feature = "anyhow", cfg(test))]
-fn format_err_msg(
-	err:  &(dyn std::error::Error + Send + Sync),
-) -> String {
-	// Error message starts with the Display implementation.
-	let mut err_msg = format!("{err}\n");  
+// Hints added on their own for what the error comes down to.
+pub(crate) fn automatic_hints(err: &dyn std::error::Error) -> Vec<String> {
+	io_hint(err).into_iter().chain(parse_hint(err)).collect()
+}
 
-	// Add the error messages of the original's sources to the message.
+// Most sources which are followed, so absurdly deep chains
+// can't exhaust the memory.
+const CHAIN_LIMIT: usize = 1024;
+
+// The error's sources, starting with the direct source. The chain
+// is cut off where it leads back to an error which was already visited.
+pub(crate) fn sources<'a>(err: &'a dyn std::error::Error) -> Vec<&'a (dyn std::error::Error + 'static)> {
+	let mut visited = vec![err as *const dyn std::error::Error];
+	let mut sources = Vec::new();
 	let mut current = err.source();
-	while let Some(cause) = current {
-		let cause_msg = format!("Caused by: {cause}\n");
-		err_msg.push_str(&cause_msg);
-		// Get option to next source.
-		current = cause.source();
+	while let Some(source) = current {
+		let ptr = source as *const dyn std::error::Error;
+		if sources.len() == CHAIN_LIMIT || visited.iter().any(|&seen| std::ptr::eq(seen, ptr)) {
+			break;
+		}
+		visited.push(ptr);
+		sources.push(source);
+		current = source.source();
 	}
+	sources
+}
+
+// The messages of the error's sources, starting with the direct source.
+pub(crate) fn cause_messages(err: &dyn std::error::Error) -> Vec<String> {
+	sources(err).into_iter()
+		.map(|source| source_parse_error(source).map_or_else(|| source.to_string(), |parse_error| parse_error.flat_message()))
+		.collect()
+}
+
+// Where the error was hinted or given up on, which is
+// only displayed in verbose mode.
+pub(crate) fn verbose_location(location: &Location, opts: &FormatOptions) -> Option<String> {
+	let verbose = opts.verbosity == Verbosity::Verbose;
+	verbose.then(|| format!("{}:{}", location.file(), location.line()))
+}
 
-	err_msg
+// The `Debug` representation of an error, which is only displayed
+// in verbose mode and if it isn't the primary message anyway.
+pub(crate) fn verbose_debug(err: &dyn std::fmt::Debug, opts: &FormatOptions) -> Option<String> {
+	let verbose = opts.verbosity == Verbosity::Verbose && !opts.prefer_debug;
+	verbose.then(|| format!("{err:#?}"))
 }
 
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::options::MessagePosition;
+	use crate::output::format_output;
+	use crate::theme::Theme;
 	use std::error::Error;
 	use std::fmt::{self, Display};
 
@@ -189,63 +773,669 @@ mod tests {
 		}
 	}
 	
-	cfg_if::cfg_if!{
-		if #[cfg(feature = "anyhow")] {
-			#[test]
-			fn formatting_of_anyhow_errors_does_not_deviate() {
-				// Assert that an error wrapped in an `anyhow::Error` is displayed
-				// the same way as a raw error.
-
-				let raw_err = FlatErr {};		
-				let raw_err_msg = format_err_msg(&raw_err);
-				let anyhow_res: anyhow::Result<()> = Err(anyhow::Error::new(raw_err));
-				let anyhow_err_msg = anyhow_res.unwrap_err().format_err_msg();
-				assert_eq!(raw_err_msg, anyhow_err_msg);
-			}
-		} else {
-			#[test]
-			fn error_messages_are_correct_for_flat_errors() {
-				// Assert that an error without a `source` is correctly formatted.
-				let flat_err = FlatErr {};
-				let result = flat_err.format_err_msg();
-				assert_eq!(result, format!("{FLAT_SRC_MSG}\n"));
-			}
+	#[cfg(feature = "eyre")]
+	#[test]
+	fn formatting_of_eyre_reports_does_not_deviate() {
+		// Assert that an error wrapped in an `eyre::Report` is displayed
+		// the same way as a raw error.
+		use crate::ErrorAdapter;
+		let opts = FormatOptions::default();
+		let raw_err_msg = error_segments(&MultiSourceErr {}, &opts).format_err_msg(&opts);
+		let report = ErrorAdapter::new(eyre::Report::new(MultiSourceErr {}));
+		assert_eq!(report.format_err_msg(), raw_err_msg);
+	}
+
+	#[cfg(feature = "eyre")]
+	#[test]
+	fn eyre_context_is_part_of_the_chain() {
+		// Assert that context added with eyre is displayed as the outer most error.
+		use crate::ErrorAdapter;
+		use eyre::WrapErr;
+		let res: eyre::Result<()> = Err(eyre::Report::new(FlatErr {})).wrap_err(SINGLE_SRC_MSG);
+		let report = ErrorAdapter::new(res.unwrap_err()).segments(&FormatOptions::default());
+		assert_eq!(report.primary, SINGLE_SRC_MSG);
+		assert_eq!(report.causes, vec![FLAT_SRC_MSG]);
+	}
+
+	#[cfg(feature = "anyhow")]
+	#[test]
+	fn formatting_of_anyhow_errors_does_not_deviate() {
+		// Assert that an error wrapped in an `anyhow::Error` is displayed
+		// the same way as a raw error.
+		use crate::ErrorAdapter;
+		let raw_err = FlatErr {};		
+		let opts = FormatOptions::default();
+		let raw_err_msg = error_segments(&raw_err, &opts).format_err_msg(&opts);
+		let anyhow_err = anyhow::Error::new(raw_err);
+		let anyhow_err_msg = ErrorAdapter::new(anyhow_err).format_err_msg();
+		assert_eq!(raw_err_msg, anyhow_err_msg);
+	}
+
+	#[cfg(feature = "anyhow")]
+	#[test]
+	fn hinted_errors_keep_their_hint_in_anyhow_errors() {
+		// Assert that converting a `HintedError` into an `anyhow::Error`
+		// preserves the hint and example.
+		use crate::{ErrorAdapter, Example, HintedError};
+
+		let hinted: Result<(), _> = Err(HintedError::with_hint(SingleSourceErr {}, HINT_MSG));
+		let anyhow_err: anyhow::Error = hinted.example(EXAMPLE_MSG).unwrap_err().into();
+		assert_eq!(
+			ErrorAdapter::new(anyhow_err).format_err_msg(),
+			format!("{SINGLE_SRC_MSG}\nCaused by: {FLAT_SRC_MSG}\n{HINT_MSG}: `{EXAMPLE_MSG}`\n"),
+		);
+	}
+
+	#[cfg(feature = "anyhow")]
+	#[test]
+	fn hints_attached_to_anyhow_errors_are_displayed() {
+		// Assert that a hint attached deep inside an anyhow chain is displayed
+		// without changing the rest of the error message.
+		use crate::{AnyhowHint, ErrorAdapter};
+		use anyhow::Context;
+
+		let anyhow_res: anyhow::Result<()> = Err(anyhow::Error::new(SingleSourceErr {}))
+			.with_giveup_hint(HINT_MSG)
+			.context(MULTI_SRC_MSG);
+		let report = ErrorAdapter::new(anyhow_res.unwrap_err()).segments(&FormatOptions::default());
+		assert_eq!(report.primary, MULTI_SRC_MSG);
+		assert_eq!(report.causes, vec![SINGLE_SRC_MSG, FLAT_SRC_MSG]);
+		assert_eq!(report.hints, vec![HINT_MSG]);
+	}
+
+	#[cfg(feature = "anyhow")]
+	#[test]
+	fn anyhow_results_and_std_errors_can_both_be_hinted() {
+		// Assert that enabling the anyhow feature doesn't take
+		// anything away from std errors.
+		use crate::{Example, GiveupReport};
+		let std_res: Result<(), FlatErr> = Err(FlatErr {});
+		let anyhow_res: anyhow::Result<()> = Err(anyhow::Error::new(FlatErr {}));
+		let std_res = std_res.hint(HINT_MSG);
+		let anyhow_res = anyhow_res.hint(HINT_MSG).example(EXAMPLE_MSG);
+		assert_eq!(std_res.unwrap_err().format_err_msg(), format!("{FLAT_SRC_MSG}\n{HINT_MSG}\n"));
+		assert_eq!(
+			anyhow_res.unwrap_err().format_err_msg(),
+			format!("{FLAT_SRC_MSG}\n{HINT_MSG}: `{EXAMPLE_MSG}`\n"),
+		);
+	}
+
+	#[test]
+	fn error_messages_are_correct_for_flat_errors() {
+		// Assert that an error without a `source` is correctly formatted.
+		let flat_err = FlatErr {};
+		let result = flat_err.format_err_msg();
+		assert_eq!(result, format!("{FLAT_SRC_MSG}\n"));
+	}
+
+	#[test]
+	fn error_messages_are_correct_for_errors_with_a_source() {
+		// Assert that an error which has a `source` is correctly formatted.
+		let single_src_err = SingleSourceErr {};
+		let result = single_src_err.format_err_msg();
+		assert_eq!(result, format!("{SINGLE_SRC_MSG}\nCaused by: {FLAT_SRC_MSG}\n"));
+	}
+
+	#[test]
+	fn error_messages_are_correct_for_multi_source_errors() {
+		// Assert that an error which has multiple sources is correctly formatted.
+		let multi_src_err = MultiSourceErr {};
+		let result = multi_src_err.format_err_msg();
+		assert_eq!(result, format!("{MULTI_SRC_MSG}\nCaused by: {SINGLE_SRC_MSG}\nCaused by: {FLAT_SRC_MSG}\n"));
+	}
+
+	#[test]
+	fn hints_are_added_correctly() {
+		// Assert that errors are correctly combined and formatted with hints.
+		let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+		let with_hint = raw_result.hint(HINT_MSG);
+		let err_msg = with_hint.unwrap_err().format_err_msg();
+		assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\n{HINT_MSG}\n"));
+	}
+
+	#[test]
+	fn examples_are_added_correctly() {
+		// Assert that errors are correctly combinded and formatted with hints AND examples.
+		use crate::Example;
+		let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+		let with_hint = raw_result.hint(HINT_MSG);
+		let with_example = with_hint.example(EXAMPLE_MSG);
+		let err_msg = with_example.unwrap_err().format_err_msg();
+		assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\n{HINT_MSG}: `{EXAMPLE_MSG}`\n"));
+	}
+
+	#[test]
+	fn empty_example_lists_add_nothing() {
+		// Assert that an empty list of examples only leaves the hint.
+		use crate::Example;
+		let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+		let with_examples = raw_result.hint(HINT_MSG).examples(Vec::<&str>::new());
+		let err_msg = with_examples.unwrap_err().format_err_msg();
+		assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\n{HINT_MSG}\n"));
+	}
+
+	#[test]
+	fn single_example_lists_match_single_examples() {
+		// Assert that a list with one example is displayed like `example`.
+		use crate::Example;
+		let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+		let with_examples = raw_result.hint(HINT_MSG).examples([EXAMPLE_MSG]);
+		let err_msg = with_examples.unwrap_err().format_err_msg();
+		assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\n{HINT_MSG}: `{EXAMPLE_MSG}`\n"));
+	}
+
+	#[test]
+	fn multiple_examples_are_displayed_on_their_own_lines() {
+		// Assert that each of multiple examples is put on its own line.
+		use crate::Example;
+		let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+		let with_examples = raw_result.hint(HINT_MSG).examples(["first", "second", "third"]);
+		let err_msg = with_examples.unwrap_err().format_err_msg();
+		assert_eq!(
+			err_msg,
+			format!("{FLAT_SRC_MSG}\n{HINT_MSG}:\n    `first`\n    `second`\n    `third`\n"),
+		);
+	}
+
+	#[test]
+	fn multiple_hints_are_displayed_as_a_list() {
+		// Assert that hinting a hinted error adds to its hints.
+		use crate::Example;
+		let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+		let err_msg = raw_result.hint("first").hint("second").unwrap_err().format_err_msg();
+		assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\n- first\n- second\n"));
+
+		let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+		let with_example = raw_result.hint("first").hint("second").example(EXAMPLE_MSG);
+		let err_msg = with_example.unwrap_err().format_err_msg();
+		assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\n- first\n- second\n    `{EXAMPLE_MSG}`\n"));
+	}
+
+	#[test]
+	fn platform_hints_are_only_displayed_on_their_platform() {
+		// Assert that exactly one of the platform hints is displayed.
+		let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+		let err_msg = raw_result.hint_unix("unix").hint_windows("windows").unwrap_err().format_err_msg();
+		let hint = if cfg!(windows) { "windows" } else { "unix" };
+		assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\n{hint}\n"));
+	}
+
+	#[test]
+	fn notes_are_displayed_after_hints() {
+		// Assert that notes are prefixed and put below the hint.
+		use crate::{Example, Note};
+		let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+		let with_notes = raw_result.hint(HINT_MSG).note("first").example(EXAMPLE_MSG).note("second");
+		let err_msg = with_notes.unwrap_err().format_err_msg();
+		assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\n{HINT_MSG}: `{EXAMPLE_MSG}`\nnote: first\nnote: second\n"));
+	}
+
+	#[test]
+	fn hints_take_anything_displayable() {
+		// Assert that none of the parts has to be a string.
+		use crate::{ErrorCode, Example, Note, SeeAlso};
+		let port = 8080;
+		let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+		let hinted = raw_result.hint(HINT_MSG)
+			.examples((1..=2).map(|n| format!("app --retry {n}")))
+			.note(format_args!("Port {port} is in use"))
+			.see_also(port)
+			.code('E')
+			.unwrap_err();
+		let report = hinted.segments(&FormatOptions::default());
+		let commands: Vec<&str> = report.examples.iter().map(|example| example.command.as_str()).collect();
+		assert_eq!(commands, vec!["app --retry 1", "app --retry 2"]);
+		assert_eq!(report.notes, vec!["Port 8080 is in use".to_owned()]);
+		assert_eq!(report.links, vec!["8080".to_owned()]);
+		assert_eq!(report.code.as_deref(), Some("E"));
+	}
+
+	#[test]
+	fn snippets_are_displayed_before_hints() {
+		// Assert that the position is taken from the error if it isn't given.
+		use crate::Snippet;
+		let raw_result: Result<(), std::io::Error> = Err(std::io::Error::other("expected name at line 1 column 2"));
+		let err_msg = raw_result.hint(HINT_MSG).source_file("Cargo.toml").unwrap_err().format_err_msg();
+		assert_eq!(err_msg, format!(concat!(
+			"expected name at line 1 column 2\n",
+			" --> Cargo.toml:1:2\n",
+			"  |\n",
+			"1 | [package]\n",
+			"  |  ^\n",
+			"{}\n",
+		), HINT_MSG));
+	}
+
+	#[test]
+	fn labeled_examples_are_listed() {
+		// Assert that labeled examples are listed below the hint even if there is only one.
+		use crate::Example;
+		let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+		let with_examples = raw_result.hint(HINT_MSG).labeled_example("with defaults", "app init");
+		let err_msg = with_examples.unwrap_err().format_err_msg();
+		assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\n{HINT_MSG}:\n    with defaults: `app init`\n"));
+
+		let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+		let with_examples = raw_result.hint(HINT_MSG)
+			.example("app init")
+			.labeled_example("custom path", "app init -c path");
+		let err_msg = with_examples.unwrap_err().format_err_msg();
+		assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\n{HINT_MSG}:\n    `app init`\n    custom path: `app init -c path`\n"));
+	}
+
+	#[test]
+	fn examples_can_be_added_without_a_hint() {
+		// Assert that an example alone is displayed below the error.
+		use crate::testing::capture;
+		let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+		let err_msg = raw_result.with_example(EXAMPLE_MSG).unwrap_err().format_err_msg();
+		assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\n`{EXAMPLE_MSG}`\n"));
+
+		let captured = capture(|| None::<()>.with_example(EXAMPLE_MSG).giveup("message"));
+		assert_eq!(captured, vec![(format!("message\n`{EXAMPLE_MSG}`\n"), 1)]);
+	}
+
+	#[test]
+	fn reports_match_the_displayed_output() {
+		// Assert that `report` returns what `giveup` displays.
+		use crate::testing::capture;
+		let result = || -> Result<(), MultiSourceErr> { Err(MultiSourceErr {}) };
+		let captured = capture(|| result().hint(HINT_MSG).giveup("message"));
+		let report = result().hint(HINT_MSG).report("message").unwrap_err();
+		assert_eq!(captured, vec![(report, 1)]);
+		assert_eq!(None::<()>.report("message"), Err("message\n".to_owned()));
+		assert_eq!(Some(42).report("message"), Ok(42));
+	}
 
-			#[test]
-			fn error_messages_are_correct_for_errors_with_a_source() {
-				// Assert that an error which has a `source` is correctly formatted.
-				let single_src_err = SingleSourceErr {};
-				let result = single_src_err.format_err_msg();
-				assert_eq!(result, format!("{SINGLE_SRC_MSG}\nCaused by: {FLAT_SRC_MSG}\n"));
+	#[test]
+	fn messages_can_be_anything_displayable() {
+		let path = std::path::Path::new("config.toml");
+		let result = || -> Result<(), FlatErr> { Err(FlatErr {}) };
+		let report = result().report(format_args!("Failed to read {}", path.display())).unwrap_err();
+		assert_eq!(report, format!("Failed to read config.toml: {FLAT_SRC_MSG}\n"));
+		assert_eq!(None::<()>.report(42), Err("42\n".to_owned()));
+	}
+
+	#[test]
+	fn hints_and_examples_can_be_anything_displayable() {
+		use crate::Example;
+		let profile = String::from("dev");
+		let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+		let hinted = raw_result
+			.hint(format_args!("Create the {profile} profile"))
+			.example(format_args!("app init {profile}"));
+		let err_msg = hinted.unwrap_err().format_err_msg();
+		assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\nCreate the dev profile: `app init dev`\n"));
+	}
+
+	#[test]
+	fn lazy_hints_are_only_computed_for_errors() {
+		use crate::Example;
+		let ok: Result<u8, FlatErr> = Ok(1);
+		let hinted = ok.hint_with(|| -> String { unreachable!() }).example_with(|| -> String { unreachable!() });
+		assert_eq!(hinted.unwrap(), 1);
+
+		let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+		let hinted = raw_result.hint_with(|| HINT_MSG).example_with(|| EXAMPLE_MSG);
+		assert_eq!(hinted.unwrap_err().format_err_msg(), format!("{FLAT_SRC_MSG}\n{HINT_MSG}: `{EXAMPLE_MSG}`\n"));
+	}
+
+	#[test]
+	fn complaining_continues_the_program() {
+		// Assert that complaining about an error doesn't give up.
+		use crate::testing::capture;
+		let captured = capture(|| {
+			assert_eq!(Err::<(), _>(FlatErr {}).hint(HINT_MSG).complain("message"), None);
+			assert_eq!(Ok::<_, FlatErr>(42).complain("message"), Some(42));
+			assert_eq!(None::<()>.complain("message"), None);
+			assert_eq!(Err::<(), _>(FlatErr {}).warn("message"), None);
+			assert_eq!(Some(42).warn("message"), Some(42));
+		});
+		assert!(captured.is_empty());
+	}
+
+	#[test]
+	fn giving_up_with_a_fallback_continues_with_it() {
+		// Assert that the fallback values are returned instead of giving up.
+		use crate::testing::capture;
+		let captured = capture(|| {
+			assert_eq!(Err(FlatErr {}).hint(HINT_MSG).giveup_or(7, "message"), 7);
+			assert_eq!(Err::<u8, _>(FlatErr {}).giveup_or_default("message"), 0);
+			assert_eq!(Ok::<_, FlatErr>(42).giveup_or(7, "message"), 42);
+			assert_eq!(None::<String>.giveup_or_default("message"), "");
+		});
+		assert!(captured.is_empty());
+	}
+
+	#[test]
+	fn errors_which_are_not_thread_safe_are_formatted() {
+		// Assert that errors don't need to be `Send` or `Sync`.
+		use std::rc::Rc;
+
+		#[derive(Debug)]
+		struct LocalErr(Rc<str>);
+		impl Error for LocalErr {}
+		impl Display for LocalErr {
+			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				write!(f, "{}", self.0)
 			}
+		}
+
+		let raw_result: Result<(), LocalErr> = Err(LocalErr(Rc::from(FLAT_SRC_MSG)));
+		let err_msg = raw_result.hint(HINT_MSG).unwrap_err().format_err_msg();
+		assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\n{HINT_MSG}\n"));
+	}
+
+	#[test]
+	fn errors_pick_the_exit_code() {
+		// Assert that the exit code is taken from the error.
+		use crate::testing::capture;
 
-			#[test]
-			fn error_messages_are_correct_for_multi_source_errors() {
-				// Assert that an error which has multiple sources is correctly formatted.
-				let multi_src_err = MultiSourceErr {};
-				let result = multi_src_err.format_err_msg();
-				assert_eq!(result, format!("{MULTI_SRC_MSG}\nCaused by: {SINGLE_SRC_MSG}\nCaused by: {FLAT_SRC_MSG}\n"));
+		struct CodedErr {}
+		impl GiveupExitCode for CodedErr {
+			fn exit_code(&self) -> i32 {
+				78
 			}
+		}
+		impl GiveupFormatError for CodedErr {
+			fn segments(&self, _opts: &FormatOptions) -> ErrorReport {
+				ErrorReport {
+					primary: FLAT_SRC_MSG.to_owned(),
+					..Default::default()
+				}
+			}
+		}
+
+		let captured = capture(|| Err::<(), _>(CodedErr {}).giveup("message"));
+		assert_eq!(captured, vec![(format!("message: {FLAT_SRC_MSG}\n"), 78)]);
 
-			#[test]
-			fn hints_are_added_correctly() {
-				// Assert that errors are correctly combined and formatted with hints.
-				let raw_result: Result<(), FlatErr> = Err(FlatErr {});
-				let with_hint = raw_result.hint(HINT_MSG);
-				let err_msg = with_hint.unwrap_err().format_err_msg();
-				assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\n{HINT_MSG}\n"));
+		let captured = capture(|| Err::<(), _>(CodedErr {}).hint(HINT_MSG).giveup("message"));
+		assert_eq!(captured, vec![(format!("message: {FLAT_SRC_MSG}\n{HINT_MSG}\n"), 78)]);
+
+		let captured = capture(|| Err::<(), _>(FlatErr {}).giveup("message"));
+		assert_eq!(captured, vec![(format!("message: {FLAT_SRC_MSG}\n"), 1)]);
+	}
+
+	#[test]
+	fn messages_are_displayed_before_errors_by_default() {
+		// Assert that the default layout is `<message>: <error>`.
+		use crate::format_error;
+		let err_msg = format_error(&SingleSourceErr {}, "message", &FormatOptions::default());
+		assert_eq!(err_msg, format!("message: {SINGLE_SRC_MSG}\nCaused by: {FLAT_SRC_MSG}\n"));
+	}
+
+	#[test]
+	fn messages_can_be_displayed_after_errors() {
+		// Assert that the message can follow the error's first line.
+		use crate::format_error;
+		let opts = FormatOptions {
+			message_position: MessagePosition::After,
+			separator: " — while ".into(),
+			..Default::default()
+		};
+		let err_msg = format_error(&SingleSourceErr {}, "message", &opts);
+		assert_eq!(err_msg, format!("{SINGLE_SRC_MSG} — while message\nCaused by: {FLAT_SRC_MSG}\n"));
+	}
+
+	#[test]
+	fn causes_can_be_hidden() {
+		// Assert that hiding the causes keeps the error itself and the hint.
+		use crate::{format_error, Example};
+		let opts = FormatOptions { show_causes: false, ..Default::default() };
+		let raw_result: Result<(), MultiSourceErr> = Err(MultiSourceErr {});
+		let with_example = raw_result.hint(HINT_MSG).example(EXAMPLE_MSG);
+		let err_msg = format_error(&with_example.unwrap_err(), "message", &opts);
+		assert_eq!(err_msg, format!("message: {MULTI_SRC_MSG}\n{HINT_MSG}: `{EXAMPLE_MSG}`\n"));
+	}
+
+	#[test]
+	fn messages_are_padded_to_the_minimum_width() {
+		// Assert that messages of different lengths are aligned.
+		let opts = FormatOptions { message_min_width: Some(8), ..Default::default() };
+		let report = ErrorReport { primary: "error".to_owned(), ..ErrorReport::default() };
+		let short = format_output("short", &report, "error\n", &opts, &Theme::plain());
+		let long = format_output("too long!", &report, "error\n", &opts, &Theme::plain());
+		assert_eq!(short, "short   : error\n");
+		assert_eq!(long, "too long!: error\n");
+
+		let unpadded = format_output("short", &report, "error\n", &FormatOptions::default(), &Theme::plain());
+		assert_eq!(unpadded, "short: error\n");
+	}
+
+	#[test]
+	fn padding_ignores_styling() {
+		// Assert that the padding is the same with and without color.
+		fn strip_styling(s: &str) -> String {
+			let mut stripped = String::new();
+			let mut chars = s.chars();
+			while let Some(c) = chars.next() {
+				if c == '\x1b' {
+					chars.by_ref().find(|&c| c == 'm');
+				} else {
+					stripped.push(c);
+				}
 			}
+			stripped
+		}
+
+		let opts = FormatOptions { message_min_width: Some(8), ..Default::default() };
+		let report = ErrorReport { primary: "error".to_owned(), ..ErrorReport::default() };
+		let colored = format_output("short", &report, "error\n", &opts, &Theme::default());
+		assert_eq!(colored, "\x1b[1mshort\x1b[0m   : error\n");
+		assert_eq!(strip_styling(&colored), format_output("short", &report, "error\n", &opts, &Theme::plain()));
+	}
+
+	#[test]
+	fn lazy_messages_are_only_built_for_errors() {
+		// Assert that the message closure is only called on the error path.
+		use crate::testing::capture;
+
+		let mut calls = 0;
+		let value = Ok::<_, FlatErr>(42).giveup_with(|| {
+			calls += 1;
+			"message"
+		});
+		assert_eq!((value, calls), (42, 0));
 
-			#[test]
-			fn examples_are_added_correctly() {
-				// Assert that errors are correctly combinded and formatted with hints AND examples.
-				use crate::Example;
-				let raw_result: Result<(), FlatErr> = Err(FlatErr {});
-				let with_hint = raw_result.hint(HINT_MSG);
-				let with_example = with_hint.example(EXAMPLE_MSG);
-				let err_msg = with_example.unwrap_err().format_err_msg();
-				assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\n{HINT_MSG}: `{EXAMPLE_MSG}`\n"));
+		let captured = capture(|| Err::<(), _>(FlatErr {}).giveup_with(|| format!("message {}", 42)));
+		assert_eq!(captured, vec![(format!("message 42: {FLAT_SRC_MSG}\n"), 1)]);
+
+		let captured = capture(|| None::<()>.giveup_with(|| String::from("message")));
+		assert_eq!(captured, vec![("message\n".to_owned(), 1)]);
+	}
+
+	#[test]
+	fn exit_codes_can_be_chosen_by_the_caller() {
+		// Assert that the given exit code is used.
+		use crate::testing::capture;
+
+		assert_eq!(Ok::<_, FlatErr>(42).giveup_code("message", 64), 42);
+
+		let captured = capture(|| Err::<(), _>(FlatErr {}).hint(HINT_MSG).giveup_code("message", 64));
+		assert_eq!(captured, vec![(format!("message: {FLAT_SRC_MSG}\n{HINT_MSG}\n"), 64)]);
+
+		let captured = capture(|| None::<()>.giveup_code("message", 2));
+		assert_eq!(captured, vec![("message\n".to_owned(), 2)]);
+	}
+
+	#[test]
+	fn hints_and_examples_can_be_owned() {
+		// Assert that hints and examples can be built at runtime and outlive their sources.
+		use crate::{Example, HintedError};
+
+		fn hinted(name: &str) -> Result<(), HintedError<FlatErr>> {
+			let raw_result: Result<(), FlatErr> = Err(FlatErr {});
+			raw_result.hint(format!("Create {name}")).example(format!("touch {name}"))
+		}
+
+		let err_msg = hinted("config").unwrap_err().format_err_msg();
+		assert_eq!(err_msg, format!("{FLAT_SRC_MSG}\nCreate config: `touch config`\n"));
+	}
+
+	#[test]
+	fn options_with_values_are_unwrapped() {
+		// Assert that `Some` values are returned as is.
+		use crate::Example;
+		assert_eq!(Some(42).giveup("message"), 42);
+		assert_eq!(Some(42).hint(HINT_MSG).example(EXAMPLE_MSG).giveup("message"), 42);
+	}
+
+	#[test]
+	fn missing_values_only_display_the_message() {
+		// Assert that `None` displays the message and hint without an error.
+		use crate::testing::capture;
+		use crate::Example;
+
+		let captured = capture(|| None::<()>.giveup("message"));
+		assert_eq!(captured, vec![("message\n".to_owned(), 1)]);
+
+		let captured = capture(|| None::<()>.hint(HINT_MSG).example(EXAMPLE_MSG).giveup("message"));
+		assert_eq!(captured, vec![(format!("message\n{HINT_MSG}: `{EXAMPLE_MSG}`\n"), 1)]);
+	}
+
+	#[test]
+	fn codes_are_displayed_in_front_of_the_message() {
+		// Assert that the outer most code is displayed in the label.
+		use crate::testing::capture;
+		use crate::ErrorCode;
+		let captured = capture(|| {
+			Err::<(), _>(FlatErr {}).hint(HINT_MSG).code("E001").hint(HINT_MSG).code("E012").giveup("message")
+		});
+		assert_eq!(captured[0].0, format!("error[E012]: message: {FLAT_SRC_MSG}\n- {HINT_MSG}\n- {HINT_MSG}\n"));
+	}
+
+	#[test]
+	fn app_names_are_displayed_first() {
+		// Assert that the app name is put in front of everything else.
+		let opts = FormatOptions { app_name: Some("myapp".into()), ..Default::default() };
+		let report = ErrorReport { primary: "error".to_owned(), ..ErrorReport::default() };
+		assert_eq!(format_output("message", &report, "error\n", &opts, &Theme::plain()), "myapp: message: error\n");
+		assert_eq!(format_output("message", &ErrorReport::default(), "", &opts, &Theme::plain()), "myapp: message\n");
+	}
+
+	#[test]
+	fn quiet_output_is_a_single_line() {
+		// Assert that quiet output hides causes and hints.
+		use crate::{format_error, Example, Verbosity};
+		let opts = FormatOptions { verbosity: Verbosity::Quiet, ..Default::default() };
+		let raw_result: Result<(), MultiSourceErr> = Err(MultiSourceErr {});
+		let with_example = raw_result.hint(HINT_MSG).example(EXAMPLE_MSG);
+		let err_msg = format_error(&with_example.unwrap_err(), "message", &opts);
+		assert_eq!(err_msg, format!("message: {MULTI_SRC_MSG}\n"));
+	}
+
+	#[test]
+	fn verbose_output_shows_everything() {
+		// Assert that verbose output adds the `Debug` representation
+		// and shows causes even if they are hidden otherwise.
+		use crate::format_error;
+		let opts = FormatOptions {
+			verbosity: Verbosity::Verbose,
+			show_causes: false,
+			..Default::default()
+		};
+		let err_msg = format_error(&SingleSourceErr {}, "message", &opts);
+		assert_eq!(err_msg, format!("message: {SINGLE_SRC_MSG}\nCaused by: {FLAT_SRC_MSG}\nDebug:\nSingleSourceErr\n"));
+
+		// The `Debug` representation isn't repeated if it's the primary message.
+		let opts = FormatOptions { prefer_debug: true, ..opts };
+		assert_eq!(SingleSourceErr {}.segments(&opts).debug, None);
+	}
+
+	#[test]
+	fn verbose_output_shows_where_the_error_was_hinted() {
+		// Assert that the location of the innermost hint is displayed.
+		let opts = FormatOptions { verbosity: Verbosity::Verbose, ..Default::default() };
+		let line = line!() + 1;
+		let hinted = Err::<(), _>(FlatErr {}).hint(HINT_MSG).hint(HINT_MSG).unwrap_err();
+		let report = hinted.segments(&opts);
+		assert_eq!(report.location, Some(format!("{}:{line}", file!())));
+		assert!(report.format_err_msg(&opts).contains(&format!("\nat {}:{line}\n", file!())));
+		assert_eq!(hinted.segments(&FormatOptions::default()).location, None);
+	}
+
+	#[test]
+	fn repeated_causes_are_collapsed() {
+		// Assert that only consecutive repetitions are collapsed.
+		let report = ErrorReport {
+			primary: "error".to_owned(),
+			causes: ["failed", "failed", "failed", "refused", "failed"].map(String::from).to_vec(),
+			..ErrorReport::default()
+		};
+		let opts = FormatOptions { dedupe_causes: true, ..Default::default() };
+		assert_eq!(report.format_err_msg(&opts), concat!(
+			"error\n",
+			"Caused by: failed (\u{d7}3)\n",
+			"Caused by: refused\n",
+			"Caused by: failed\n",
+		));
+		assert_eq!(report.format_err_msg(&FormatOptions::default()).lines().count(), 6);
+	}
+
+	#[test]
+	fn long_chains_are_cut_off() {
+		// Assert that verbose output displays the causes left out otherwise.
+		let report = ErrorReport {
+			primary: "error".to_owned(),
+			causes: ["a", "b", "c", "d"].map(String::from).to_vec(),
+			..ErrorReport::default()
+		};
+		let opts = FormatOptions { max_causes: Some(2), ..Default::default() };
+		assert_eq!(report.format_err_msg(&opts), concat!(
+			"error\n",
+			"Caused by: a\n",
+			"Caused by: b\n",
+			"\u{2026}and 2 more causes (run with --verbose)\n",
+		));
+		let opts = FormatOptions { verbosity: Verbosity::Verbose, ..opts };
+		assert_eq!(report.format_err_msg(&opts).lines().count(), 5);
+	}
+
+	#[test]
+	fn cyclic_chains_end() {
+		// Assert that a chain leading back to the first error ends there.
+		#[derive(Debug)]
+		struct CyclicErr(&'static str, Option<&'static CyclicErr>);
+		impl Error for CyclicErr {
+			fn source(&self) -> Option<&(dyn Error + 'static)> {
+				Some(self.1.unwrap_or(&FIRST))
 			}
 		}
+		impl Display for CyclicErr {
+			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str(self.0)
+			}
+		}
+		static SECOND: CyclicErr = CyclicErr("second", None);
+		static FIRST: CyclicErr = CyclicErr("first", Some(&SECOND));
+
+		assert_eq!(cause_messages(&FIRST), vec!["second"]);
+	}
+
+	#[test]
+	fn debug_is_used_if_preferred() {
+		// Assert that the error itself is formatted using `Debug` but
+		// its sources still use `Display`.
+		let opts = FormatOptions { prefer_debug: true, ..Default::default() };
+		let report = SingleSourceErr {}.segments(&opts);
+		assert_eq!(report.primary, "SingleSourceErr");
+		assert_eq!(report.causes, vec![FLAT_SRC_MSG]);
+
+		let report = SingleSourceErr {}.segments(&FormatOptions::default());
+		assert_eq!(report.primary, SINGLE_SRC_MSG);
+	}
+
+	#[test]
+	fn segments_are_split_correctly() {
+		// Assert that each part of a hinted multi-source error ends up in its own segment.
+		use crate::{format_segments, Example, ExampleCommand};
+		let raw_result: Result<(), MultiSourceErr> = Err(MultiSourceErr {});
+		let with_example = raw_result.hint(HINT_MSG).example(EXAMPLE_MSG);
+		let report = format_segments(&with_example.unwrap_err(), "message");
+		assert_eq!(report.message, "message");
+		assert_eq!(report.primary, MULTI_SRC_MSG);
+		assert_eq!(report.causes, vec![SINGLE_SRC_MSG, FLAT_SRC_MSG]);
+		assert_eq!(report.hints, vec![HINT_MSG]);
+		assert_eq!(report.examples, vec![ExampleCommand::new(EXAMPLE_MSG)]);
 	}
 }
@@ -1,74 +1,464 @@
-use crate::giveup::GiveupFormatError;
+use crate::config::{current_config, effective_config};
+use crate::exit::Exit;
+use crate::giveup::{giveup_report, verbose_location, GiveupExitCode, GiveupFormatError};
+use crate::options::FormatOptions;
+use crate::output::format_report;
+use crate::report::{backtrace_string, ErrorReport, ExampleCommand};
+use crate::shell::{examples_for_shell, Shell};
+use crate::snippet::SourceLocation;
+use std::backtrace::Backtrace;
+use std::fmt::{self, Display};
+use std::panic::Location;
+use std::process::{ExitCode, Termination};
 
 /// Add an example message to an object.
-pub trait Example<'a> {
+pub trait Example {
 	/// Consumes and returns `self` combined with the
 	/// given `example` message.
-	fn example(self, example: &'a str) -> Self;
+	fn example<S>(self, example: S) -> Self
+	where
+		S: Display;
+
+	/// Same as [`example`](Self::example), but the command is only
+	/// computed if there is an error.
+	/// # Example
+	/// ```rust
+	/// use giveup::{Example, Giveup};
+	/// let config = std::fs::read_to_string("Cargo.toml")
+	///     .hint("Create a configuration file")
+	///     .example_with(|| format!("touch {}", std::env::temp_dir().join("config.toml").display()))
+	///     .giveup("Failed to read the configuration");
+	/// ```
+	fn example_with<F, S>(self, example: F) -> Self
+	where
+		F: FnOnce() -> S,
+		S: Display;
+
+	/// Consumes and returns `self` combined with a sequence
+	/// of example commands. Each command is displayed on its
+	/// own line.
+	fn examples<I, S>(self, examples: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Display;
+
+	/// Consumes and returns `self` with another example command
+	/// added, which is described by `label`. Labeled commands are
+	/// listed on their own lines below the hint.
+	/// # Example
+	/// ```rust
+	/// use giveup::{Example, Giveup};
+	/// let config = std::fs::read_to_string("Cargo.toml")
+	///     .hint("Create a configuration file")
+	///     .labeled_example("with defaults", "app init")
+	///     .labeled_example("custom path", "app init -c path")
+	///     .giveup("Failed to read the configuration");
+	/// ```
+	fn labeled_example<L, S>(self, label: L, example: S) -> Self
+	where
+		L: Display,
+		S: Display;
 }
 
 /// Implementation of `Example` on any `Result`s returned by
 /// [`hint`](crate::Giveup::hint)
-impl<'a, T, E> Example<'a> for Result<T, HintedError<'a, E>> {
+impl<T, E> Example for Result<T, HintedError<E>> {
 	/// Set the `example` field in `self` to the
 	/// given string if `self` is an error.
-	fn example(mut self, example: &'a str) -> Self {
+	fn example<S>(mut self, example: S) -> Self
+	where
+		S: Display,
+	{
+		if let Err(ref mut e) = self {
+			e.hint.examples = vec![ExampleCommand::new(example.to_string())];
+		}
+		self
+	}
+
+	/// Set the `example` field in `self` to the string
+	/// returned by `example` if `self` is an error.
+	fn example_with<F, S>(mut self, example: F) -> Self
+	where
+		F: FnOnce() -> S,
+		S: Display,
+	{
+		if let Err(ref mut e) = self {
+			e.hint.examples = vec![ExampleCommand::new(example().to_string())];
+		}
+		self
+	}
+
+	/// Set the `examples` field in `self` to the
+	/// given commands if `self` is an error.
+	fn examples<I, S>(mut self, examples: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Display,
+	{
+		if let Err(ref mut e) = self {
+			e.hint.examples = examples.into_iter().map(ExampleCommand::new).collect();
+		}
+		self
+	}
+
+	/// Add a labeled example command if `self` is an error.
+	fn labeled_example<L, S>(mut self, label: L, example: S) -> Self
+	where
+		L: Display,
+		S: Display,
+	{
+		if let Err(ref mut e) = self {
+			e.hint.examples.push(ExampleCommand::labeled(label.to_string(), example.to_string()));
+		}
+		self
+	}
+}
+
+/// Add background information to an object.
+pub trait Note {
+	/// Consumes and returns `self` combined with the given note.
+	///
+	/// Unlike hints, notes aren't something users should act on.
+	/// They are displayed after the hints, prefixed with `note:`
+	/// and dimmed if the output is colored. Each call adds
+	/// another note.
+	/// # Example
+	/// ```rust
+	/// use giveup::{Giveup, Note};
+	/// let port: u16 = "8080".parse()
+	///     .hint("Pass a number between 0 and 65535")
+	///     .note("Ports below 1024 require root privileges")
+	///     .giveup("Invalid port");
+	/// ```
+	fn note<S>(self, note: S) -> Self
+	where
+		S: Display;
+}
+
+impl<T, E> Note for Result<T, HintedError<E>> {
+	fn note<S>(mut self, note: S) -> Self
+	where
+		S: Display,
+	{
+		if let Err(ref mut e) = self {
+			e.hint.notes.push(note.to_string());
+		}
+		self
+	}
+}
+
+impl Note for Hint {
+	fn note<S>(mut self, note: S) -> Self
+	where
+		S: Display,
+	{
+		self.notes.push(note.to_string());
+		self
+	}
+}
+
+/// Point users to further documentation.
+pub trait SeeAlso {
+	/// Consumes and returns `self` combined with a link to
+	/// documentation about the error, e.g. a troubleshooting page.
+	///
+	/// Links are displayed after the notes. If the output is styled,
+	/// they are displayed as terminal hyperlinks (OSC 8), which
+	/// terminals without support for them display as plain text.
+	/// # Example
+	/// ```rust
+	/// use giveup::{Giveup, SeeAlso};
+	/// let config = std::fs::read_to_string("Cargo.toml")
+	///     .hint("Create a configuration file")
+	///     .see_also("https://example.com/docs/config")
+	///     .giveup("Failed to read the configuration");
+	/// ```
+	fn see_also<S>(self, url: S) -> Self
+	where
+		S: Display;
+}
+
+impl<T, E> SeeAlso for Result<T, HintedError<E>> {
+	fn see_also<S>(mut self, url: S) -> Self
+	where
+		S: Display,
+	{
+		if let Err(ref mut e) = self {
+			e.hint.links.push(url.to_string());
+		}
+		self
+	}
+}
+
+impl SeeAlso for Hint {
+	fn see_also<S>(mut self, url: S) -> Self
+	where
+		S: Display,
+	{
+		self.links.push(url.to_string());
+		self
+	}
+}
+
+/// Identify an error by a short code.
+pub trait ErrorCode {
+	/// Consumes and returns `self` with the given code, e.g. `E012`.
+	///
+	/// The code is displayed in front of the message as `error[E012]:`,
+	/// so users can search the documentation for it and refer to the
+	/// error unambiguously. Setting another code replaces it.
+	/// Longer explanations of codes are registered with
+	/// [`register_code`](crate::register_code).
+	/// # Example
+	/// ```rust
+	/// use giveup::{ErrorCode, Giveup};
+	/// let config = std::fs::read_to_string("Cargo.toml")
+	///     .hint("Create a configuration file")
+	///     .code("E012")
+	///     .giveup("Missing configuration file");
+	/// ```
+	fn code<S>(self, code: S) -> Self
+	where
+		S: Display;
+}
+
+impl<T, E> ErrorCode for Result<T, HintedError<E>> {
+	fn code<S>(mut self, code: S) -> Self
+	where
+		S: Display,
+	{
 		if let Err(ref mut e) = self {
-			e.hint.example = Some(example);
+			e.hint.code = Some(code.to_string());
 		}
 		self
 	}
 }
 
+impl ErrorCode for Hint {
+	fn code<S>(mut self, code: S) -> Self
+	where
+		S: Display,
+	{
+		self.code = Some(code.to_string());
+		self
+	}
+}
+
+impl Example for Hint {
+	/// Set the example command of the hint.
+	fn example<S>(mut self, example: S) -> Self
+	where
+		S: Display,
+	{
+		self.examples = vec![ExampleCommand::new(example.to_string())];
+		self
+	}
+
+	/// Set the example command of the hint to the one returned by `example`.
+	fn example_with<F, S>(self, example: F) -> Self
+	where
+		F: FnOnce() -> S,
+		S: Display,
+	{
+		self.example(example())
+	}
+
+	/// Set the example commands of the hint.
+	fn examples<I, S>(mut self, examples: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Display,
+	{
+		self.examples = examples.into_iter().map(ExampleCommand::new).collect();
+		self
+	}
+
+	/// Add a labeled example command to the hint.
+	fn labeled_example<L, S>(mut self, label: L, example: S) -> Self
+	where
+		L: Display,
+		S: Display,
+	{
+		self.examples.push(ExampleCommand::labeled(label.to_string(), example.to_string()));
+		self
+	}
+}
 
 /// Combination of an error with user information.
-#[derive(Debug)]
-pub struct HintedError<'a, E> {
+///
+/// Returning it from `main` displays `Error: ` followed by the
+/// report [`giveup`](crate::Giveup::giveup) would display, and exits
+/// with code 1. Return an [`Exit`](crate::Exit) instead to exit with
+/// the error's exit code and run the [`on_exit`](crate::on_exit) hooks.
+/// ```rust no_run
+/// use std::fs::File;
+/// use giveup::{Example, Giveup, HintedError};
+///
+/// fn main() -> Result<(), HintedError<std::io::Error>> {
+///     let config = File::open("config-path")
+///         .hint("Create a configuration file")
+///         .example("touch config-path")?;
+///     Ok(())
+/// }
+/// ```
+pub struct HintedError<E> {
 	/// The wrapped error.
-	e: E,
-	/// Additional user information about the error.
-	hint: Hint<'a>,
+	pub(crate) e: E,
+	/// Additional user information about the error. It's boxed
+	/// to keep `Result`s with a `HintedError` small.
+	pub(crate) hint: Box<Hint>,
+	/// Where the hint was added.
+	pub(crate) backtrace: Backtrace,
+	/// The call which added the hint.
+	pub(crate) location: &'static Location<'static>,
+}
+
+impl<E> HintedError<E> {
+	/// Create a new error wrapper without a hint. Examples
+	/// can still be added to it.
+	#[track_caller]
+	pub fn new(e: E) -> Self {
+		Self {
+			e,
+			hint: Box::default(),
+			backtrace: current_config().capture_backtrace(),
+			location: Location::caller(),
+		}
+	}
+
+	/// Create a new error wrapper which combines the given error with
+	/// a hint on how to resolve the error.
+	///
+	/// A backtrace is captured if backtraces are enabled with `RUST_BACKTRACE`,
+	/// `RUST_LIB_BACKTRACE` or [`ConfigBuilder::backtrace`](crate::ConfigBuilder::backtrace).
+	#[track_caller]
+	pub fn with_hint<S>(e: E, hint: S) -> Self
+	where
+		S: Display,
+	{
+		let mut err = Self::new(e);
+		err.hint.hint = Some(hint.to_string());
+		err
+	}
 }
 
-impl<'a, E> HintedError<'a, E>
+impl<E> GiveupExitCode for HintedError<E>
 where
 	E: GiveupFormatError,
 {
-	/// Create a new error wrapper which combines the given error with
-	/// a hint on how to resolve the error.
-	pub fn with_hint(e: E, hint: &'a str) -> Self {
-		Self {
-			e,
-			hint: Hint{ hint, example: None },
+	fn exit_code(&self) -> i32 {
+		self.e.exit_code()
+	}
+}
+
+// `main` returning `Result<(), HintedError<E>>` prints the Debug
+// representation, so it's the report without the message. `{:#?}`
+// shows the fields instead.
+impl<E> fmt::Debug for HintedError<E>
+where
+	E: GiveupFormatError + fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if f.alternate() {
+			return f.debug_struct("HintedError")
+				.field("e", &self.e)
+				.field("hint", &self.hint)
+				.field("location", &self.location)
+				.finish();
 		}
+		let config = effective_config();
+		let report = giveup_report(self, "");
+		let report = config.redacted(&report);
+		// `main` adds the trailing newline itself.
+		f.write_str(format_report(&report, &config, false).trim_end())
 	}
 }
 
-impl<'a, E> GiveupFormatError for HintedError<'a, E>
+// Returning the error itself from `main` displays it like an `Exit` does.
+impl<E> Termination for HintedError<E>
 where
 	E: GiveupFormatError,
 {
-	fn format_err_msg(&self) -> String {
+	fn report(self) -> ExitCode {
+		Exit::new(Err(self)).report()
+	}
+}
+
+impl<E> GiveupFormatError for HintedError<E>
+where
+	E: GiveupFormatError,
+{
+	fn segments(&self, opts: &FormatOptions) -> ErrorReport {
 		// The hinted error message is made up of the raw error
-		// message followed by the hint.
-		format!("{}{}\n", self.e.format_err_msg(), self.hint)
+		// message followed by the hint. Hinting an already hinted
+		// error adds another hint.
+		let mut report = self.e.segments(opts);
+		self.hint.add_to(&mut report);
+		// The innermost backtrace and location are closest to the failure.
+		if report.backtrace.is_none() {
+			report.backtrace = backtrace_string(&self.backtrace);
+		}
+		if report.location.is_none() {
+			report.location = verbose_location(self.location, opts);
+		}
+		report
 	}
 }
 
 /// Information on events which is meant
 /// for users to act on the event correctly.
-#[derive(Debug)]
-struct Hint<'a> {
-	hint: &'a str,
-	example: Option<&'a str>,
-}
-
-impl<'a> std::fmt::Display for Hint<'a> {
-	/// Print a `Hint` instance.
-	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-		match self.example {
-			Some(example) => write!(f, "{}: `{}`", self.hint, example),
-			None => write!(f, "{}", self.hint),
+///
+/// Example commands are added using [`Example`]. A `Hint` created
+/// with `Default` only displays its examples.
+/// # Example
+/// ```rust
+/// use giveup::{Example, Hint};
+/// let hint = Hint::new("Create a configuration file")
+///     .example("touch config-path");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hint {
+	pub(crate) hint: Option<String>,
+	pub(crate) suggestion: Option<String>,
+	pub(crate) examples: Vec<ExampleCommand>,
+	pub(crate) shell_examples: Vec<(Shell, ExampleCommand)>,
+	pub(crate) notes: Vec<String>,
+	pub(crate) links: Vec<String>,
+	pub(crate) code: Option<String>,
+	pub(crate) snippet: Option<SourceLocation>,
+}
+
+impl Hint {
+	/// Create a hint with the given text.
+	pub fn new<S>(hint: S) -> Self
+	where
+		S: Display,
+	{
+		Self {
+			hint: Some(hint.to_string()),
+			..Self::default()
+		}
+	}
+
+	// Add everything in the hint to the report of the error.
+	pub(crate) fn add_to(&self, report: &mut ErrorReport) {
+		// Hints given by hand are more specific than the built-in ones.
+		if self.hint.is_some() || self.suggestion.is_some() {
+			report.hints.drain(..report.auto_hints);
+			report.auto_hints = 0;
+		}
+		report.hints.extend(self.suggestion.clone());
+		report.hints.extend(self.hint.clone());
+		report.examples.extend(self.examples.iter().cloned());
+		report.examples.extend(examples_for_shell(&self.shell_examples));
+		report.notes.extend(self.notes.iter().cloned());
+		report.links.extend(self.links.iter().cloned());
+		// The outer most code replaces the codes of inner errors.
+		if self.code.is_some() {
+			report.code = self.code.clone();
+		}
+		if let Some(snippet) = self.snippet.as_ref().and_then(|location| location.render(report)) {
+			report.snippet = Some(snippet);
 		}
 	}
 }
@@ -0,0 +1,121 @@
+use crate::hint::{Hint, HintedError};
+
+/// Errors which know how users can resolve them.
+///
+/// Library error types can implement this to ship their own
+/// guidance along with the error. With the `derive` feature it
+/// can be derived using `#[hint("...")]` and `#[example("...")]`
+/// attributes on the type or on the variants of an enum.
+///
+/// The hint is picked up when the error is converted into a
+/// [`HintedError`], e.g. by `?` or `HintedError::from`.
+/// # Example
+/// ```rust
+/// use giveup::{Example, Hint, Hintable};
+///
+/// enum ConfigError {
+///     Missing,
+///     Invalid,
+/// }
+///
+/// impl Hintable for ConfigError {
+///     fn hint(&self) -> Option<Hint> {
+///         match self {
+///             Self::Missing => Some(Hint::new("Create a configuration file").example("touch config-path")),
+///             Self::Invalid => None,
+///         }
+///     }
+/// }
+/// ```
+pub trait Hintable {
+	/// The hint displayed with this error, if there is any.
+	fn hint(&self) -> Option<Hint>;
+}
+
+/// Wrap an error together with its own hint, so `?` picks up
+/// the hint when returning a `HintedError` (e.g. from `main`).
+/// # Example
+/// ```rust no_run
+/// use giveup::{Hint, Hintable, HintedError};
+/// use std::fmt;
+///
+/// #[derive(Debug)]
+/// struct MissingConfig;
+///
+/// impl fmt::Display for MissingConfig {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         write!(f, "The configuration file is missing")
+///     }
+/// }
+///
+/// impl std::error::Error for MissingConfig {}
+///
+/// impl Hintable for MissingConfig {
+///     fn hint(&self) -> Option<Hint> {
+///         Some(Hint::new("Create a configuration file"))
+///     }
+/// }
+///
+/// fn load_config() -> Result<String, MissingConfig> {
+///     Err(MissingConfig)
+/// }
+///
+/// fn main() -> Result<(), HintedError<MissingConfig>> {
+///     let config = load_config()?;
+///     Ok(())
+/// }
+/// ```
+impl<E> From<E> for HintedError<E>
+where
+	E: Hintable,
+{
+	#[track_caller]
+	fn from(e: E) -> Self {
+		let hint = e.hint().unwrap_or_default();
+		let mut err = HintedError::new(e);
+		*err.hint = hint;
+		err
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Example;
+
+	struct Unhinted;
+
+	impl Hintable for Unhinted {
+		fn hint(&self) -> Option<Hint> {
+			None
+		}
+	}
+
+	struct Hinted;
+
+	impl Hintable for Hinted {
+		fn hint(&self) -> Option<Hint> {
+			Some(Hint::new("hint").example("example"))
+		}
+	}
+
+	fn propagate<E>(result: Result<(), E>) -> Result<(), HintedError<E>>
+	where
+		E: Hintable,
+	{
+		result?;
+		Ok(())
+	}
+
+	#[test]
+	fn question_marks_pick_up_hints() {
+		let err = propagate(Err(Hinted)).unwrap_err();
+		assert_eq!(*err.hint, Hint::new("hint").example("example"));
+	}
+
+	#[test]
+	fn errors_without_hints_stay_unhinted() {
+		let err = propagate(Err(Unhinted)).unwrap_err();
+		assert_eq!(*err.hint, Hint::default());
+	}
+}
@@ -0,0 +1,184 @@
+use crate::report::ErrorReport;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+type ExitHook = Box<dyn FnOnce() + Send>;
+type SuspendHook = Arc<dyn Fn(&mut dyn FnMut()) + Send + Sync>;
+
+// Callbacks run right before giving up, in reverse registration order.
+static EXIT_HOOKS: Mutex<Vec<ExitHook>> = Mutex::new(Vec::new());
+
+// Callback which is passed the report the program gives up with.
+static REPORT_HOOK: RwLock<Option<fn(&ErrorReport)>> = RwLock::new(None);
+
+// Callback which displays errors while progress bars and alike are hidden.
+static SUSPEND_HOOK: RwLock<Option<SuspendHook>> = RwLock::new(None);
+
+/// Register a callback which is run right before the program gives up.
+///
+/// Giving up exits the process without running any destructors, so
+/// this is the place to remove temporary files, restore the terminal
+/// (e.g. leave raw mode) or clear progress bars. Hooks run before the
+/// error is displayed, in reverse order of registration, and each of
+/// them runs at most once. A hook which panics doesn't keep the others
+/// from running.
+/// # Example
+/// ```rust
+/// let tmp = std::env::temp_dir().join("giveup-example");
+/// giveup::on_exit(move || {
+///     let _ = std::fs::remove_file(&tmp);
+/// });
+/// ```
+pub fn on_exit<F>(hook: F)
+where
+	F: FnOnce() + Send + 'static,
+{
+	EXIT_HOOKS.lock().unwrap_or_else(PoisonError::into_inner).push(Box::new(hook));
+}
+
+// Run and remove all registered exit hooks.
+pub(crate) fn run_exit_hooks() {
+	// The lock isn't held while the hooks run, so they can register more hooks.
+	let hooks = std::mem::take(&mut *EXIT_HOOKS.lock().unwrap_or_else(PoisonError::into_inner));
+	for hook in hooks.into_iter().rev() {
+		let _ = panic::catch_unwind(AssertUnwindSafe(hook));
+	}
+}
+
+/// Set the function which is passed the report of the error the
+/// program gives up on, e.g. to forward it to a telemetry service.
+///
+/// It's called after the error is displayed, right before the process
+/// exits. Only the last function set is called. Errors which aren't
+/// given up on (e.g. by [`complain`](crate::Giveup::complain)) aren't
+/// passed to it.
+/// # Example
+/// ```rust
+/// fn send_to_telemetry(report: &giveup::ErrorReport) {
+///     // The report can be serialized using `to_json`.
+///     let _body = report.to_json();
+/// }
+///
+/// giveup::set_report_hook(send_to_telemetry);
+/// ```
+pub fn set_report_hook(hook: fn(&ErrorReport)) {
+	*REPORT_HOOK.write().unwrap_or_else(PoisonError::into_inner) = Some(hook);
+}
+
+// Pass the report to the report hook, if there is one.
+pub(crate) fn run_report_hook(report: &ErrorReport) {
+	let hook = *REPORT_HOOK.read().unwrap_or_else(PoisonError::into_inner);
+	if let Some(hook) = hook {
+		let _ = panic::catch_unwind(AssertUnwindSafe(|| hook(report)));
+	}
+}
+
+/// Set the function which hides progress bars, spinners and alike
+/// while an error is displayed, so the error isn't interleaved with
+/// their redraws.
+///
+/// The hook is passed a function which displays the error. It must
+/// call it once, e.g. from within indicatif's `ProgressBar::suspend`.
+/// If it doesn't, the error is displayed right after the hook returns.
+/// Only the last function set is called.
+/// # Example
+/// ```rust
+/// giveup::set_suspend_hook(|display| {
+///     // Clear the line of the spinner, then redraw it afterwards.
+///     eprint!("\r\x1b[2K");
+///     display();
+/// });
+/// ```
+pub fn set_suspend_hook<F>(hook: F)
+where
+	F: Fn(&mut dyn FnMut()) + Send + Sync + 'static,
+{
+	*SUSPEND_HOOK.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(hook));
+}
+
+// Call `display` from within the suspend hook, if there is one.
+pub(crate) fn suspended<F>(display: F)
+where
+	F: FnOnce(),
+{
+	// The lock isn't held while the hook runs, so it can replace itself.
+	let hook = SUSPEND_HOOK.read().unwrap_or_else(PoisonError::into_inner).clone();
+	suspend_with(hook.as_deref(), display);
+}
+
+fn suspend_with<H, F>(hook: Option<&H>, display: F)
+where
+	H: Fn(&mut dyn FnMut()) + ?Sized,
+	F: FnOnce(),
+{
+	let mut display = Some(display);
+	if let Some(hook) = hook {
+		let mut once = || {
+			if let Some(display) = display.take() {
+				display();
+			}
+		};
+		let _ = panic::catch_unwind(AssertUnwindSafe(|| hook(&mut once)));
+	}
+	// The error is displayed even if the hook didn't do it.
+	if let Some(display) = display {
+		display();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::{Arc, Mutex};
+
+	#[test]
+	fn hooks_run_once_in_reverse_order() {
+		let order = Arc::new(Mutex::new(Vec::new()));
+		for idx in 0..3 {
+			let order = Arc::clone(&order);
+			on_exit(move || order.lock().unwrap().push(idx));
+		}
+		on_exit(|| panic!("failing hook"));
+
+		// Keep the message of the failing hook out of the test output,
+		// but not those of other tests panicking in the meantime.
+		let previous: Arc<dyn Fn(&panic::PanicHookInfo) + Send + Sync> = panic::take_hook().into();
+		let others = Arc::clone(&previous);
+		panic::set_hook(Box::new(move |info| {
+			if info.payload().downcast_ref::<&str>() != Some(&"failing hook") {
+				others(info);
+			}
+		}));
+		run_exit_hooks();
+		run_exit_hooks();
+		panic::set_hook(Box::new(move |info| previous(info)));
+		assert_eq!(*order.lock().unwrap(), vec![2, 1, 0]);
+	}
+
+	#[test]
+	fn report_hook_gets_the_report() {
+		static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+		set_report_hook(|report| MESSAGES.lock().unwrap().push(report.message.clone()));
+
+		run_report_hook(&ErrorReport::with_message("message"));
+		assert_eq!(*MESSAGES.lock().unwrap(), vec!["message".to_owned()]);
+	}
+
+	#[test]
+	fn errors_are_displayed_once_while_suspended() {
+		static EVENTS: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+		let push = |event| EVENTS.lock().unwrap().push(event);
+
+		let hook = |display: &mut dyn FnMut()| {
+			push("hide");
+			display();
+			display();
+			push("show");
+		};
+		suspend_with(Some(&hook), || push("display"));
+		// Hooks which forget to display the error don't swallow it.
+		suspend_with(Some(&|_: &mut dyn FnMut()| {}), || push("display"));
+
+		assert_eq!(*EVENTS.lock().unwrap(), vec!["hide", "display", "show", "display"]);
+	}
+}
@@ -0,0 +1,155 @@
+//! Translated messages, hints and examples using Fluent.
+
+use fluent::concurrent::FluentBundle;
+use fluent::{FluentArgs, FluentResource, FluentValue};
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::{PoisonError, RwLock};
+
+// Translations messages are looked up in.
+static BUNDLE: RwLock<Option<FluentBundle<FluentResource>>> = RwLock::new(None);
+
+/// Install the translations used to resolve [`Localized`] messages.
+///
+/// Unicode isolation marks around arguments are turned off,
+/// because terminals display them.
+/// # Example
+/// ```rust
+/// use fluent::concurrent::FluentBundle;
+/// use fluent::FluentResource;
+///
+/// let ftl = "config-missing = Die Konfigurationsdatei { $path } fehlt".to_owned();
+/// let mut bundle = FluentBundle::new_concurrent(vec!["de-DE".parse().unwrap()]);
+/// bundle.add_resource(FluentResource::try_new(ftl).unwrap()).unwrap();
+/// giveup::set_bundle(bundle);
+///
+/// let msg = giveup::localized!("config-missing", path = "app.toml");
+/// assert_eq!(msg.to_string(), "Die Konfigurationsdatei app.toml fehlt");
+/// ```
+pub fn set_bundle(mut bundle: FluentBundle<FluentResource>) {
+	bundle.set_use_isolating(false);
+	*BUNDLE.write().unwrap_or_else(PoisonError::into_inner) = Some(bundle);
+}
+
+/// The user's preferred locale based on `LC_ALL`, `LC_MESSAGES`
+/// and `LANG`, e.g. `de-DE` for `LANG=de_DE.UTF-8`.
+///
+/// Use it to pick the translations passed to [`set_bundle`].
+pub fn env_locale() -> Option<String> {
+	["LC_ALL", "LC_MESSAGES", "LANG"].into_iter()
+		.filter_map(|name| std::env::var(name).ok())
+		.find(|value| !value.is_empty())
+		.and_then(|value| parse_locale(&value))
+}
+
+// Turn a POSIX locale like `de_DE.UTF-8@euro` into `de-DE`.
+fn parse_locale(value: &str) -> Option<String> {
+	let locale = value.split(['.', '@']).next().unwrap_or_default();
+	match locale {
+		"" | "C" | "POSIX" => None,
+		locale => Some(locale.replace('_', "-")),
+	}
+}
+
+/// ID of a translated message and its arguments, which is resolved
+/// once it's converted into a `String` or displayed.
+///
+/// [`hint`](crate::Giveup::hint), [`example`](crate::Example::example),
+/// etc. only convert it if there is an error, so it's resolved at the time
+/// of the report. If there is no translation for the ID, the ID itself
+/// is used. Create it using [`localized!`](crate::localized).
+/// # Example
+/// ```rust
+/// use giveup::{localized, Giveup};
+/// let count: u32 = "3".parse()
+///     .hint(localized!("count-hint", max = 10))
+///     .giveup_with(|| localized!("count-invalid").to_string());
+/// ```
+#[derive(Debug)]
+pub struct Localized {
+	id: Cow<'static, str>,
+	args: FluentArgs<'static>,
+}
+
+impl Localized {
+	/// Refer to the message with the given ID.
+	pub fn new<S>(id: S) -> Self
+	where
+		S: Into<Cow<'static, str>>,
+	{
+		Self {
+			id: id.into(),
+			args: FluentArgs::new(),
+		}
+	}
+
+	/// Pass an argument to the message, e.g. `$path`.
+	pub fn arg<K, V>(mut self, name: K, value: V) -> Self
+	where
+		K: Into<Cow<'static, str>>,
+		V: Into<FluentValue<'static>>,
+	{
+		self.args.set(name, value);
+		self
+	}
+}
+
+impl fmt::Display for Localized {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let bundle = BUNDLE.read().unwrap_or_else(PoisonError::into_inner);
+		let pattern = bundle.as_ref()
+			.and_then(|bundle| Some((bundle, bundle.get_message(&self.id)?.value()?)));
+		match pattern {
+			Some((bundle, pattern)) => {
+				// Errors are resolved to something readable, e.g. missing
+				// arguments to their names, so they are ignored.
+				let mut errors = Vec::new();
+				f.write_str(&bundle.format_pattern(pattern, Some(&self.args), &mut errors))
+			},
+			None => f.write_str(&self.id),
+		}
+	}
+}
+
+impl From<Localized> for String {
+	fn from(localized: Localized) -> Self {
+		localized.to_string()
+	}
+}
+
+/// Create a [`Localized`] message from its ID and arguments.
+/// # Example
+/// ```rust
+/// let msg = giveup::localized!("config-missing", path = "app.toml", attempts = 3);
+/// ```
+#[macro_export]
+macro_rules! localized {
+	($id:expr $(, $name:ident = $value:expr)* $(,)?) => {
+		$crate::Localized::new($id)$(.arg(stringify!($name), $value))*
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn locales_are_parsed() {
+		assert_eq!(parse_locale("de_DE.UTF-8").as_deref(), Some("de-DE"));
+		assert_eq!(parse_locale("ca_ES@valencia").as_deref(), Some("ca-ES"));
+		assert_eq!(parse_locale("C.UTF-8"), None);
+		assert_eq!(parse_locale("POSIX"), None);
+	}
+
+	#[test]
+	fn messages_are_resolved_with_their_arguments() {
+		let ftl = "attempts = { $count ->\n    [one] One attempt\n   *[other] { $count } attempts\n}\n";
+		let mut bundle = FluentBundle::new_concurrent(vec!["en".parse().unwrap()]);
+		bundle.add_resource(FluentResource::try_new(ftl.to_owned()).unwrap()).unwrap();
+		set_bundle(bundle);
+
+		assert_eq!(localized!("attempts", count = 1).to_string(), "One attempt");
+		assert_eq!(String::from(localized!("attempts", count = 3)), "3 attempts");
+		assert_eq!(localized!("missing-id").to_string(), "missing-id");
+	}
+}
@@ -0,0 +1,100 @@
+//! Hints added automatically if an error comes down to an `io::Error`.
+
+use crate::config::current_config;
+use crate::giveup::sources;
+use std::error::Error;
+use std::io::{self, ErrorKind};
+
+// Hints used unless they are replaced using `ConfigBuilder::io_hint`.
+fn default_io_hint(kind: ErrorKind) -> Option<&'static str> {
+	match kind {
+		ErrorKind::NotFound => Some("Check that the path exists"),
+		ErrorKind::PermissionDenied => Some("Check the file's permissions or try running with elevated privileges"),
+		ErrorKind::AlreadyExists => Some("Remove the existing file or choose another path"),
+		ErrorKind::ConnectionRefused => Some("Check that the server is running and the address is correct"),
+		ErrorKind::TimedOut => Some("Check your network connection and try again"),
+		ErrorKind::AddrInUse => Some("Stop the program using the address or choose another one"),
+		_ => None,
+	}
+}
+
+// The hint for the kind of the error at the end of the chain,
+// if that's an `io::Error`.
+pub(crate) fn io_hint(err: &dyn Error) -> Option<String> {
+	let kind = terminal_kind(err)?;
+	let config = current_config();
+	match config.io_hints.iter().rev().find(|(overridden, _)| *overridden == kind) {
+		Some((_, hint)) => hint.as_ref().map(|hint| hint.to_string()),
+		None => default_io_hint(kind).map(str::to_owned),
+	}
+}
+
+fn terminal_kind(err: &dyn Error) -> Option<ErrorKind> {
+	match sources(err).last() {
+		Some(source) => source.downcast_ref::<io::Error>().map(io::Error::kind),
+		// The error given up on can't be downcast, because it doesn't
+		// have to be `'static`. OS errors are recognized by their message.
+		None => os_error_kind(&err.to_string()),
+	}
+}
+
+// The kind of an OS error from its message, e.g.
+// `No such file or directory (os error 2)`.
+pub(crate) fn os_error_kind(msg: &str) -> Option<ErrorKind> {
+	let code = msg.strip_suffix(')')?.rsplit_once("(os error ")?.1;
+	Some(io::Error::from_raw_os_error(code.parse().ok()?).kind())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::giveup::GiveupFormatError;
+
+	#[cfg(unix)]
+	#[test]
+	fn os_errors_are_recognized_by_their_message() {
+		let err = io::Error::from_raw_os_error(13);
+		assert_eq!(terminal_kind(&err), Some(ErrorKind::PermissionDenied));
+		assert_eq!(os_error_kind("no os error here"), None);
+	}
+
+	#[test]
+	fn hints_depend_on_the_end_of_the_chain() {
+		#[derive(Debug)]
+		struct ReadError(io::Error);
+		impl Error for ReadError {
+			fn source(&self) -> Option<&(dyn Error + 'static)> {
+				Some(&self.0)
+			}
+		}
+		impl std::fmt::Display for ReadError {
+			fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+				f.write_str("Failed to read")
+			}
+		}
+
+		let err = ReadError(io::Error::new(ErrorKind::NotFound, "missing"));
+		assert_eq!(io_hint(&err).as_deref(), Some("Check that the path exists"));
+		let err = ReadError(io::Error::new(ErrorKind::InvalidData, "invalid"));
+		assert_eq!(io_hint(&err), None);
+	}
+
+	#[test]
+	fn hints_given_by_hand_replace_built_in_ones() {
+		use crate::Giveup;
+		let result: Result<(), _> = Err(io::Error::from_raw_os_error(2));
+		assert_eq!(result.as_ref().unwrap_err().segments(&Default::default()).hints, vec!["Check that the path exists"]);
+		let err = result.hint("Create a configuration file").unwrap_err();
+		assert_eq!(err.segments(&Default::default()).hints, vec!["Create a configuration file"]);
+	}
+
+	#[test]
+	fn hints_given_by_hand_are_kept_even_if_they_match_a_built_in_one() {
+		use crate::Giveup;
+		let result: Result<(), _> = Err(io::Error::from_raw_os_error(2));
+		let err = result.hint("Check that the path exists").hint("Create a configuration file").unwrap_err();
+		let report = err.segments(&Default::default());
+		assert_eq!(report.hints, vec!["Check that the path exists", "Create a configuration file"]);
+		assert_eq!(report.auto_hints, 0);
+	}
+}
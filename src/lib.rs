@@ -9,10 +9,6 @@
 //! ## Example
 //!
 //! ```rust should_panic
-//! # cfg_if::cfg_if!{
-//!     # if #[cfg(feature = "anyhow")] {
-//!        # panic!()  // required to get tests to pass
-//!     # } else {
 //! # use std::fs::File;
 //! # use giveup::{Giveup, Example};
 //! // Here reading the config at the start of the cli app
@@ -21,8 +17,6 @@
 //!     .hint("Create a configuration file")
 //!     .example("touch config-filename")
 //!     .giveup("Missing configuration file");
-//!     # }
-//! # }
 //! ```
 //!
 //! ## Motivation
@@ -51,6 +45,13 @@
 //! Also `giveup` is more friendly to dynamic error messages
 //! using variables.
 //!
+//! ## Requirements
+//!
+//! `giveup` needs the standard library. Giving up exits the process,
+//! reports are written to stderr and the configuration is shared by
+//! the whole process, none of which exists in `no_std` environments.
+//! `no_std` support isn't planned for now.
+//!
 //! ## Feedback
 //!
 //! I primarily wrote `giveup` for my personal use so I would love
@@ -58,7 +59,107 @@
 //!
 //!
 
+mod adapter;
+#[cfg(feature = "anyhow")]
+mod anyhow_hint;
+mod bail;
+mod batch;
+#[cfg(feature = "buffered-stderr")]
+mod buffered_stderr;
+mod catalog;
+#[cfg(feature = "clap")]
+mod clap_error;
+#[cfg(feature = "clipboard")]
+mod clipboard;
+mod command;
+mod config;
+mod crash_report;
+#[cfg(feature = "miette")]
+mod diagnostic;
+mod ensure;
+mod exit;
+mod exit_code_map;
+mod formatter;
+mod future;
 mod giveup;
+mod github;
 mod hint;
-pub use crate::giveup::Giveup;
-pub use crate::hint::Example;
+mod hintable;
+mod hooks;
+mod io_hint;
+mod library;
+#[cfg(feature = "i18n")]
+mod i18n;
+mod lock;
+#[cfg(feature = "log")]
+mod logging;
+mod multi;
+mod options;
+mod output;
+mod panic;
+mod parse_error;
+mod prompt;
+mod redact;
+mod report;
+mod retry;
+mod shell;
+#[cfg(feature = "signal-safe")]
+mod signal_safe;
+mod snippet;
+mod strings;
+mod suggest;
+#[cfg(feature = "tokio")]
+mod task;
+#[cfg(unix)]
+mod system_log;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod theme;
+mod timestamp;
+#[cfg(feature = "tracing")]
+mod trace;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+mod wasm;
+mod wrap;
+pub use crate::adapter::{ErrorAdapter, GiveupReport};
+#[cfg(feature = "anyhow")]
+pub use crate::anyhow_hint::AnyhowHint;
+pub use crate::bail::MessageError;
+#[doc(hidden)]
+pub use crate::bail::__message_error;
+pub use crate::batch::GiveupAll;
+pub use crate::catalog::{explain, register_code, Explanation};
+#[cfg(feature = "clap")]
+pub use crate::clap_error::ClapError;
+pub use crate::command::{CommandError, GiveupCommand, GiveupStatus, StatusError};
+pub use crate::config::{config, ConfigBuilder, OutputMode, Terminator};
+pub use crate::ensure::{giveup, giveup_ensure, giveup_ensure_with};
+pub use crate::exit::Exit;
+pub use crate::exit_code_map::{sysexits, ExitCodeMap};
+pub use crate::formatter::{DefaultFormatter, MarkdownFormatter, ReportFormatter, TemplateFormatter};
+pub use crate::future::GiveupFuture;
+pub use crate::giveup::{Giveup, GiveupExitCode, GiveupFormatError, NoneError};
+pub use crate::hint::{ErrorCode, Example, Hint, HintedError, Note, SeeAlso};
+pub use crate::hintable::Hintable;
+pub use crate::hooks::{on_exit, set_report_hook, set_suspend_hook};
+#[cfg(feature = "i18n")]
+pub use crate::i18n::{env_locale, set_bundle, Localized};
+pub use crate::library::Report;
+pub use crate::lock::GiveupLock;
+pub use crate::multi::MultiError;
+pub use crate::options::{set_format_options, set_verbosity, FormatOptions, MessagePosition, Verbosity, Wrap};
+pub use crate::panic::{install_panic_hook, install_panic_hook_with_location, PanicHook};
+pub use crate::redact::{redact_home_dir, shorten_paths};
+pub use crate::report::{format_error, format_segments, ErrorReport, ExampleCommand, Severity};
+pub use crate::retry::{retry_or_giveup, retry_or_giveup_with_backoff};
+pub use crate::shell::{Shell, ShellExample};
+#[cfg(feature = "signal-safe")]
+pub use crate::signal_safe::giveup_signal_safe;
+pub use crate::snippet::Snippet;
+pub use crate::strings::Strings;
+pub use crate::suggest::{suggest, DidYouMean};
+#[cfg(feature = "tokio")]
+pub use crate::task::TaskError;
+pub use crate::theme::{Color, Style, Theme};
+#[cfg(feature = "derive")]
+pub use giveup_derive::Hintable;
@@ -0,0 +1,172 @@
+//! Composed reports as error values, for library crates which
+//! leave it to the binary when to give up.
+
+use crate::config::DEFAULT_CONFIG;
+use crate::giveup::{GiveupExitCode, GiveupFormatError};
+use crate::options::FormatOptions;
+use crate::output::format_report;
+use crate::report::ErrorReport;
+use std::error::Error;
+use std::fmt;
+
+/// Error carrying the report [`giveup`](crate::Giveup::giveup)
+/// would have displayed, as returned by
+/// [`giveup_err`](crate::Giveup::giveup_err).
+///
+/// Giving up on it displays the message passed to `giveup_err` as the
+/// error, followed by the original error as its first cause, and keeps
+/// the hints, examples and exit code. Like `anyhow::Error`, it doesn't
+/// implement [`std::error::Error`] itself, but converts into a boxed one.
+/// # Example
+/// ```rust should_panic
+/// use giveup::{Giveup, Report};
+///
+/// // In a library crate.
+/// fn load_config() -> Result<String, Report> {
+///     std::fs::read_to_string("config-path")
+///         .hint("Create a configuration file")
+///         .giveup_err("Failed to read the configuration")
+/// }
+///
+/// // In the binary.
+/// let config = load_config().giveup("Failed to start");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Report {
+	// Boxed, so results with a `Report` stay small.
+	report: Box<ErrorReport>,
+	code: i32,
+}
+
+impl Report {
+	pub(crate) fn new(report: ErrorReport, code: i32) -> Self {
+		Self {
+			report: Box::new(report),
+			code,
+		}
+	}
+
+	/// The report as it would have been displayed.
+	pub fn report(&self) -> &ErrorReport {
+		&self.report
+	}
+
+	/// Take the report out of the error.
+	pub fn into_report(self) -> ErrorReport {
+		*self.report
+	}
+}
+
+impl fmt::Display for Report {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		// Only the error itself, independent of how the program
+		// configured its output, as callers embed it in their own.
+		let report = ErrorReport {
+			message: self.report.message.clone(),
+			primary: self.report.primary.clone(),
+			causes: self.report.causes.clone(),
+			..ErrorReport::default()
+		};
+		let output = format_report(&report, &DEFAULT_CONFIG, false);
+		f.write_str(output.trim_end())
+	}
+}
+
+impl GiveupFormatError for Report {
+	fn segments(&self, _opts: &FormatOptions) -> ErrorReport {
+		let report = ErrorReport::clone(&self.report);
+		if report.message.is_empty() {
+			return report;
+		}
+		ErrorReport {
+			message: String::new(),
+			primary: report.message,
+			// Reports from `Option`s have no error to become a cause.
+			causes: Some(report.primary)
+				.filter(|primary| !primary.is_empty())
+				.into_iter()
+				.chain(report.causes)
+				.collect(),
+			..report
+		}
+	}
+}
+
+impl GiveupExitCode for Report {
+	fn exit_code(&self) -> i32 {
+		self.code
+	}
+}
+
+// `Report` as a `std::error::Error`, which can't be implemented
+// for `Report` itself because of `GiveupFormatError`'s blanket impl.
+#[derive(Debug)]
+struct ReportError(Report);
+
+impl fmt::Display for ReportError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+impl Error for ReportError {}
+
+impl From<Report> for Box<dyn Error + Send + Sync> {
+	fn from(report: Report) -> Self {
+		Box::new(ReportError(report))
+	}
+}
+
+impl From<Report> for Box<dyn Error> {
+	fn from(report: Report) -> Self {
+		Box::new(ReportError(report))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::capture;
+	use crate::{Example, Giveup};
+
+	fn load_config() -> Result<String, Report> {
+		std::fs::read_to_string("missing.toml")
+			.hint("Create a configuration file")
+			.example("touch missing.toml")
+			.giveup_err("Failed to read the configuration")
+	}
+
+	#[test]
+	fn reports_keep_their_parts() {
+		let err = load_config().unwrap_err();
+		assert_eq!(err.report().message, "Failed to read the configuration");
+		assert_eq!(err.report().hints, vec!["Create a configuration file".to_owned()]);
+		assert_eq!(
+			err.to_string(),
+			"Failed to read the configuration: No such file or directory (os error 2)"
+		);
+		let boxed: Box<dyn Error + Send + Sync> = err.into();
+		assert!(boxed.to_string().starts_with("Failed to read the configuration"));
+	}
+
+	#[test]
+	fn giving_up_nests_the_report() {
+		let captured = capture(|| {
+			load_config().giveup("Failed to start");
+		});
+		let output = concat!(
+			"Failed to start: Failed to read the configuration\n",
+			"Caused by: No such file or directory (os error 2)\n",
+			"Create a configuration file: `touch missing.toml`\n",
+		);
+		assert_eq!(captured, vec![(output.to_owned(), 1)]);
+	}
+
+	#[test]
+	fn reports_from_options_have_no_empty_cause() {
+		let captured = capture(|| {
+			None::<u8>.giveup_err("inner").giveup("outer");
+		});
+		assert_eq!(captured, vec![("outer: inner\n".to_owned(), 1)]);
+	}
+}
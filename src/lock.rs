@@ -0,0 +1,124 @@
+use crate::config::default_exit_code;
+use crate::giveup::with_caller_context;
+use crate::output::{exit_gracefully, print_report};
+use crate::report::{ErrorReport, Severity};
+use std::fmt::Display;
+use std::sync::PoisonError;
+
+// Explanation displayed for poisoned locks.
+const POISONED_MSG: &str = "a thread panicked while holding this lock";
+
+/// User-geared handling of poisoned locks.
+///
+/// Implemented for the results of locking a `Mutex` or `RwLock`.
+pub trait GiveupLock<G> {
+	/// Return the lock's guard or terminate the program
+	/// gracefully if the lock is poisoned.
+	/// # Example
+	/// ```rust
+	/// use std::sync::Mutex;
+	/// use giveup::GiveupLock;
+	/// let config = Mutex::new(42);
+	/// let config = config.lock().giveup_lock("Failed to read the configuration");
+	/// assert_eq!(*config, 42);
+	/// ```
+	fn giveup_lock<M>(self, msg: M) -> G
+	where
+		M: Display;
+
+	/// Return the lock's guard even if the lock is poisoned.
+	/// A warning is displayed in that case because the data
+	/// protected by the lock might be inconsistent.
+	fn recover_lock<M>(self, msg: M) -> G
+	where
+		M: Display;
+}
+
+impl<G> GiveupLock<G> for Result<G, PoisonError<G>> {
+	#[track_caller]
+	fn giveup_lock<M>(self, msg: M) -> G
+	where
+		M: Display,
+	{
+		match self {
+			Ok(guard) => guard,
+			Err(_) => exit_gracefully(&poisoned_report(&msg.to_string()), default_exit_code()),
+		}
+	}
+
+	#[track_caller]
+	fn recover_lock<M>(self, msg: M) -> G
+	where
+		M: Display,
+	{
+		match self {
+			Ok(guard) => guard,
+			Err(err) => {
+				let mut report = poisoned_report(&msg.to_string());
+				report.severity = Severity::Warning;
+				report.notes.push("Continuing with the data the thread left behind".to_owned());
+				print_report(&report);
+				err.into_inner()
+			},
+		}
+	}
+}
+
+#[track_caller]
+fn poisoned_report(msg: &str) -> ErrorReport {
+	with_caller_context(ErrorReport {
+		message: msg.to_owned(),
+		primary: POISONED_MSG.to_owned(),
+		..ErrorReport::default()
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::{capture, capture_printed};
+	use std::panic;
+	use std::sync::Mutex;
+
+	fn poisoned_mutex() -> Mutex<u8> {
+		let mutex = Mutex::new(42);
+		let _ = panic::catch_unwind(|| {
+			let _guard = mutex.lock().unwrap();
+			panic!("poisoning the mutex");
+		});
+		assert!(mutex.is_poisoned());
+		mutex
+	}
+
+	#[test]
+	fn healthy_locks_return_the_guard() {
+		let mutex = Mutex::new(42);
+		assert_eq!(*mutex.lock().giveup_lock("message"), 42);
+		assert_eq!(*mutex.lock().recover_lock("message"), 42);
+	}
+
+	#[test]
+	fn poisoned_locks_give_up() {
+		let mutex = poisoned_mutex();
+		let captured = capture(|| {
+			let _guard = mutex.lock().giveup_lock("message");
+		});
+		assert_eq!(captured, vec![(format!("message: {POISONED_MSG}\n"), 1)]);
+	}
+
+	#[test]
+	fn poisoned_locks_can_be_recovered() {
+		let mutex = poisoned_mutex();
+		let mut printed = Vec::new();
+		let captured = capture(|| {
+			printed = capture_printed(|| {
+				assert_eq!(*mutex.lock().recover_lock("message"), 42);
+			});
+		});
+		assert!(captured.is_empty());
+		assert_eq!(printed.len(), 1);
+		assert_eq!(printed[0].severity, Severity::Warning);
+		assert_eq!(printed[0].message, "message");
+		assert_eq!(printed[0].notes, vec!["Continuing with the data the thread left behind".to_owned()]);
+	}
+}
@@ -0,0 +1,76 @@
+//! Emitting reports using the `log` crate, so programs which log
+//! to files capture the same failure users saw.
+
+use crate::config::Config;
+use crate::output::format_report;
+use crate::report::{ErrorReport, Severity};
+use log::Level;
+
+// Target of all records emitted by `giveup`.
+const TARGET: &str = "giveup";
+
+// Log the uncolored report as an error, or as a warning for warnings.
+pub(crate) fn log_report(report: &ErrorReport, config: &Config) {
+	let level = match report.severity {
+		Severity::Warning => Level::Warn,
+		Severity::Error | Severity::Fatal => Level::Error,
+	};
+	if !log::log_enabled!(target: TARGET, level) {
+		return;
+	}
+	let output = format_report(report, config, false);
+	log::log!(
+		target: TARGET,
+		level,
+		severity = report.severity.as_str(),
+		code = report.code.as_deref();
+		"{}",
+		output.trim_end(),
+	);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::current_config;
+	use log::{Log, Metadata, Record};
+	use std::sync::Mutex;
+
+	static RECORDS: Mutex<Vec<(Level, String)>> = Mutex::new(Vec::new());
+
+	struct Logger;
+
+	impl Log for Logger {
+		fn enabled(&self, _metadata: &Metadata) -> bool {
+			true
+		}
+
+		fn log(&self, record: &Record) {
+			let entry = (record.level(), record.args().to_string());
+			RECORDS.lock().unwrap().push(entry);
+		}
+
+		fn flush(&self) {}
+	}
+
+	#[test]
+	fn reports_are_logged_without_styling() {
+		log::set_logger(&Logger).unwrap();
+		log::set_max_level(log::LevelFilter::Warn);
+
+		let report = ErrorReport {
+			primary: "error".to_owned(),
+			..ErrorReport::with_message("logged message")
+		};
+		log_report(&report, &current_config());
+		let warning = ErrorReport {
+			severity: Severity::Warning,
+			..ErrorReport::with_message("logged warning")
+		};
+		log_report(&warning, &current_config());
+
+		let records = RECORDS.lock().unwrap();
+		assert!(records.contains(&(Level::Error, "logged message: error".to_owned())));
+		assert!(records.contains(&(Level::Warn, "warning: logged warning".to_owned())));
+	}
+}
@@ -0,0 +1,166 @@
+use crate::giveup::{GiveupExitCode, GiveupFormatError};
+use crate::options::FormatOptions;
+use crate::report::ErrorReport;
+use std::fmt;
+
+type BoxedError = Box<dyn GiveupFormatError>;
+
+/// Several errors which are given up on together, e.g. all
+/// the failures found during a validation pass.
+///
+/// The errors are displayed as a numbered list, each with its own
+/// causes and hints. The program exits with the
+/// [`exit_code`](GiveupExitCode::exit_code) of the first error.
+/// # Example
+/// ```rust
+/// use giveup::{Giveup, MultiError};
+///
+/// let mut errors = MultiError::new();
+/// for (file, port) in [("a.toml", "80"), ("b.toml", "8080")] {
+///     if let Err(e) = port.parse::<u16>() {
+///         errors.push_with_context(file, e);
+///     }
+/// }
+/// errors.into_result().giveup("Invalid configuration");
+/// ```
+#[derive(Default)]
+pub struct MultiError {
+	errors: Vec<(Option<String>, BoxedError)>,
+}
+
+impl MultiError {
+	/// Create an empty collection of errors.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Add another error.
+	pub fn push<E>(&mut self, e: E)
+	where
+		E: GiveupFormatError + 'static,
+	{
+		self.errors.push((None, Box::new(e)));
+	}
+
+	/// Add another error which is displayed after `context`,
+	/// e.g. the name of the file the error is about.
+	pub fn push_with_context<C, E>(&mut self, context: C, e: E)
+	where
+		C: fmt::Display,
+		E: GiveupFormatError + 'static,
+	{
+		self.errors.push((Some(context.to_string()), Box::new(e)));
+	}
+
+	/// Number of errors.
+	pub fn len(&self) -> usize {
+		self.errors.len()
+	}
+
+	/// Whether no error was added.
+	pub fn is_empty(&self) -> bool {
+		self.errors.is_empty()
+	}
+
+	/// `Ok` if no error was added, so it can be given up on.
+	pub fn into_result(self) -> Result<(), Self> {
+		if self.is_empty() {
+			Ok(())
+		} else {
+			Err(self)
+		}
+	}
+}
+
+impl GiveupFormatError for MultiError {
+	fn segments(&self, opts: &FormatOptions) -> ErrorReport {
+		let primary = match self.errors.len() {
+			1 => "1 error".to_owned(),
+			n => format!("{n} errors"),
+		};
+		let items = self.errors.iter()
+			.map(|(context, e)| {
+				let mut item = e.segments(opts);
+				if let Some(context) = context {
+					item.message = context.clone();
+				}
+				item
+			})
+			.collect();
+		ErrorReport {
+			primary,
+			items,
+			..ErrorReport::default()
+		}
+	}
+}
+
+impl GiveupExitCode for MultiError {
+	fn exit_code(&self) -> i32 {
+		self.errors.first().map_or_else(crate::config::default_exit_code, |(_, e)| e.exit_code())
+	}
+}
+
+impl fmt::Debug for MultiError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("MultiError")
+			.field("len", &self.errors.len())
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::hint::HintedError;
+
+	#[test]
+	fn errors_are_numbered() {
+		let mut errors = MultiError::new();
+		errors.push_with_context("a.toml", "x".parse::<u8>().unwrap_err());
+		errors.push(HintedError::with_hint("".parse::<u8>().unwrap_err(), "Pass a number"));
+		let err_msg = errors.format_err_msg();
+		assert_eq!(err_msg, concat!(
+			"2 errors\n",
+			"1. a.toml: invalid digit found in string\n",
+			"2. cannot parse integer from empty string\n",
+			"   Pass a number\n",
+		));
+	}
+
+	#[test]
+	fn errors_stay_structured() {
+		let mut errors = MultiError::new();
+		errors.push_with_context("a.toml", HintedError::with_hint("x".parse::<u8>().unwrap_err(), "Pass a number"));
+		let report = errors.segments(&FormatOptions::default());
+		assert_eq!(report.primary, "1 error");
+		assert_eq!(report.items[0].message, "a.toml");
+		assert_eq!(report.items[0].primary, "invalid digit found in string");
+		assert_eq!(report.items[0].hints, vec!["Pass a number".to_owned()]);
+		let themed = report.format_err_msg_styled(&FormatOptions::default(), &crate::theme::Theme::cargo());
+		assert!(themed.ends_with("   \x1b[36mPass a number\x1b[0m\n"));
+	}
+
+	#[test]
+	fn errors_may_stay_on_their_thread() {
+		#[derive(Debug)]
+		struct LocalError(std::rc::Rc<str>);
+
+		impl fmt::Display for LocalError {
+			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str(&self.0)
+			}
+		}
+
+		impl std::error::Error for LocalError {}
+
+		let mut errors = MultiError::new();
+		errors.push(LocalError("not shared".into()));
+		assert_eq!(errors.format_err_msg(), "1 error\n1. not shared\n");
+	}
+
+	#[test]
+	fn empty_errors_are_ok() {
+		assert!(MultiError::new().into_result().is_ok());
+	}
+}
@@ -0,0 +1,153 @@
+use crate::config::{current_config, env_overrides, update_config};
+use crate::strings::{Strings, DEFAULT_STRINGS};
+use std::borrow::Cow;
+
+/// Options which control how errors are formatted.
+///
+/// The options used by [`giveup`](crate::Giveup::giveup) are set
+/// with [`set_format_options`] or [`config`](crate::config).
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+	/// Format the error itself using its `Debug` implementation
+	/// instead of `Display`. Its sources are still formatted
+	/// using `Display`.
+	///
+	/// This is useful for errors which only derive `Debug` or
+	/// which have a poor `Display` implementation.
+	pub prefer_debug: bool,
+	/// Whether the message passed to `giveup` is displayed
+	/// before or after the error.
+	pub message_position: MessagePosition,
+	/// Text between the message and the error.
+	pub separator: Cow<'static, str>,
+	/// Display the `Caused by:` lines of the error's sources.
+	///
+	/// Hiding them is useful if the sources are an implementation
+	/// detail users can't act on. Hints are displayed either way.
+	pub show_causes: bool,
+	/// Collapse consecutive causes with the same message into one,
+	/// followed by how often it's repeated, e.g. `connection failed (×3)`.
+	///
+	/// Nested wrappers often repeat the message of their source.
+	pub dedupe_causes: bool,
+	/// Display at most this many causes, followed by how many were
+	/// left out. Defaults to 16. Verbose output displays all of them.
+	pub max_causes: Option<usize>,
+	/// Pad the message passed to `giveup` with trailing spaces to
+	/// this width, so the messages of related errors line up.
+	pub message_min_width: Option<usize>,
+	/// How much of the error is displayed.
+	pub verbosity: Verbosity,
+	/// Name of the program displayed in front of every message.
+	pub app_name: Option<Cow<'static, str>>,
+	/// Display `error:` in front of the message (after the app name),
+	/// e.g. `myapp: error: <message>: <error>` like cargo does.
+	pub error_label: bool,
+	/// Whether long lines are wrapped.
+	pub wrap: Wrap,
+	/// The text around the parts of the error, e.g. `Caused by:`.
+	pub strings: Strings,
+}
+
+/// How long lines of the output are wrapped.
+///
+/// Wrapped lines continue with a hanging indentation. Debug output,
+/// backtraces and words which don't fit on a line by themselves
+/// (e.g. URLs) are never split.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wrap {
+	/// Don't wrap lines.
+	Never,
+	/// Wrap lines at the width of the terminal if the output is
+	/// displayed on one (the default). The width is taken from
+	/// `COLUMNS` or the terminal itself and defaults to 80.
+	Terminal,
+	/// Always wrap lines at the given width.
+	Width(usize),
+}
+
+/// How much of an error is displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verbosity {
+	/// Only the message and the error itself on a single line.
+	Quiet,
+	/// The message, the error, its causes, hints and examples.
+	Normal,
+	/// Everything that is displayed with `Normal` plus the error's
+	/// `Debug` representation. Causes are displayed even if
+	/// [`show_causes`](FormatOptions::show_causes) is off.
+	///
+	/// Setting the `GIVEUP_DEBUG` environment variable to `1` turns
+	/// it on regardless of the verbosity set by the program, e.g.
+	/// for attaching everything to a bug report.
+	Verbose,
+}
+
+/// Position of the message passed to `giveup` relative to the error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessagePosition {
+	/// `<message>: <error>`
+	Before,
+	/// `<error><separator><message>`, e.g. `<error> — while <message>`.
+	///
+	/// The message is put at the end of the error's first line,
+	/// so its causes and hint still follow below.
+	After,
+}
+
+pub(crate) const DEFAULT_OPTIONS: FormatOptions = FormatOptions {
+	prefer_debug: false,
+	message_position: MessagePosition::Before,
+	separator: Cow::Borrowed(": "),
+	show_causes: true,
+	dedupe_causes: false,
+	max_causes: Some(16),
+	message_min_width: None,
+	verbosity: Verbosity::Normal,
+	app_name: None,
+	error_label: false,
+	wrap: Wrap::Terminal,
+	strings: DEFAULT_STRINGS,
+};
+
+impl Default for FormatOptions {
+	fn default() -> Self {
+		DEFAULT_OPTIONS
+	}
+}
+
+/// Set the options used to format errors for the whole process.
+/// # Example
+/// ```rust
+/// use giveup::{set_format_options, FormatOptions};
+/// set_format_options(FormatOptions {
+///     prefer_debug: true,
+///     ..Default::default()
+/// });
+/// ```
+pub fn set_format_options(opts: FormatOptions) {
+	update_config(|config| config.format = opts);
+}
+
+/// Set how much of an error is displayed for the whole process.
+///
+/// This is meant for mapping flags like `--quiet` and `--verbose`
+/// onto the output of `giveup`.
+/// # Example
+/// ```rust
+/// use giveup::{set_verbosity, Verbosity};
+/// let quiet = std::env::args().any(|arg| arg == "--quiet");
+/// if quiet {
+///     set_verbosity(Verbosity::Quiet);
+/// }
+/// ```
+pub fn set_verbosity(verbosity: Verbosity) {
+	update_config(|config| config.format.verbosity = verbosity);
+}
+
+/// Get the options currently used to format errors.
+pub(crate) fn format_options() -> FormatOptions {
+	let mut opts = current_config().format;
+	env_overrides().apply_format(&mut opts);
+	opts
+}
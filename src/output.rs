@@ -0,0 +1,558 @@
+use crate::config::{effective_config, Config, OutputMode, Terminator};
+use crate::formatter::{DefaultFormatter, ReportFormatter};
+use crate::hooks::{run_exit_hooks, run_report_hook, suspended};
+use crate::options::{FormatOptions, MessagePosition, Wrap};
+use crate::report::{ErrorReport, Severity};
+use crate::theme::{Style, Theme};
+use crate::wrap::WrapWriter;
+use std::fmt;
+
+// Display the report and terminate the program with `code`.
+pub(crate) fn exit_gracefully(report: &ErrorReport, code: i32) -> ! {
+	let config = effective_config();
+	let report = report_failure(report, code, &config);
+	terminate(config.terminator, &report, code);
+}
+
+// Do everything giving up does before the program is terminated,
+// and return the report as it was displayed.
+pub(crate) fn report_failure(report: &ErrorReport, code: i32, config: &Config) -> ErrorReport {
+	let report = &*config.redacted(report);
+
+	#[cfg(any(test, feature = "testing"))]
+	crate::testing::intercept(report, &format_report(report, config, false), code);
+	#[cfg(not(any(test, feature = "testing")))]
+	let _ = code;
+
+	// Restore the terminal and the like before anything is displayed.
+	run_exit_hooks();
+	let report = crate::crash_report::with_crash_report(report, config);
+	#[cfg(feature = "clipboard")]
+	let report = crate::clipboard::with_copied_example(&report, config);
+	print_report_with(&report, config);
+	run_report_hook(&report);
+	crate::prompt::offer_fix(&report, config);
+	report
+}
+
+// End the program the way `terminator` says.
+pub(crate) fn terminate(terminator: Terminator, report: &ErrorReport, code: i32) -> ! {
+	match terminator {
+		Terminator::Exit => {
+			cfg_if::cfg_if! {
+				if #[cfg(all(feature = "wasm", target_arch = "wasm32"))] {
+					crate::wasm::exit(code);
+				} else {
+					std::process::exit(code);
+				}
+			}
+		},
+		Terminator::Abort => std::process::abort(),
+		Terminator::Custom(terminate) => terminate(report, code),
+	}
+}
+
+// Display the report without terminating the program.
+pub(crate) fn print_report(report: &ErrorReport) {
+	let config = effective_config();
+	let report = config.redacted(report);
+	#[cfg(test)]
+	if crate::testing::record_printed(&report) {
+		return;
+	}
+	print_report_with(&report, &config);
+}
+
+// Display the report on the configured writer or stderr.
+fn print_report_with(report: &ErrorReport, config: &Config) {
+	#[cfg(feature = "log")]
+	crate::logging::log_report(report, config);
+	#[cfg(feature = "tracing")]
+	crate::trace::record_report(report);
+
+	let color = config.color_enabled();
+	// Progress bars are hidden while the error is displayed.
+	suspended(|| {
+		match &config.writer {
+			Some(writer) => writer.write_with(|out| write!(out, "{}", DisplayReport { report, config, color })),
+			None => {
+				let config = &config.for_stderr();
+				cfg_if::cfg_if! {
+					if #[cfg(all(feature = "wasm", target_arch = "wasm32"))] {
+						crate::wasm::write_report(report, config);
+					} else if #[cfg(feature = "buffered-stderr")] {
+						crate::buffered_stderr::write_report_buffered(report, config, color);
+					} else {
+						// Stderr stays locked while the report is formatted into it.
+						eprint!("{}", DisplayReport { report, config, color });
+					}
+				}
+			},
+		}
+	});
+	if config.github_annotations_enabled() {
+		crate::github::write_annotation(report, &config.format);
+	}
+	#[cfg(unix)]
+	if config.system_log_enabled() {
+		crate::system_log::write_report(report, config);
+	}
+}
+
+// Format the report the way it is displayed with the given configuration.
+pub(crate) fn format_report(report: &ErrorReport, config: &Config, color: bool) -> String {
+	let mut output = String::new();
+	// Writing to a `String` never fails.
+	let _ = write_report(&mut output, report, config, color);
+	output
+}
+
+// Same as `format_report`, but the output is written to `out`.
+pub(crate) fn write_report<W>(
+	out: &mut W,
+	report: &ErrorReport,
+	config: &Config,
+	color: bool,
+) -> fmt::Result
+where
+	W: fmt::Write,
+{
+	match config.output {
+		OutputMode::Human => {
+			let opts = &config.format;
+			let theme = if color { &config.theme } else { &Theme::plain() };
+			let formatter: &dyn ReportFormatter = match &config.formatter {
+				Some(formatter) => formatter,
+				None => &DefaultFormatter,
+			};
+			let write = |out: &mut dyn fmt::Write| {
+				if config.timestamps {
+					write!(out, "{} ", crate::timestamp::now())?;
+				}
+				formatter.write(out, report, opts, theme)
+			};
+			match opts.wrap {
+				Wrap::Width(width) => {
+					let mut out = WrapWriter::new(out, width, &opts.strings);
+					write(&mut out)?;
+					out.finish()
+				},
+				Wrap::Never | Wrap::Terminal => write(out),
+			}
+		},
+		OutputMode::Json => writeln!(out, "{}", report.to_json()),
+	}
+}
+
+// Output of `write_report`, which is formatted as it is displayed.
+struct DisplayReport<'a> {
+	report: &'a ErrorReport,
+	config: &'a Config,
+	color: bool,
+}
+
+impl fmt::Display for DisplayReport<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write_report(f, self.report, self.config, self.color)
+	}
+}
+
+// Combine the message passed to `giveup` with the formatted error.
+// `err_msg` starts with the line of `report.primary`, which is empty
+// if the error doesn't have a message of its own.
+pub(crate) fn format_output(
+	msg: &str,
+	report: &ErrorReport,
+	err_msg: &str,
+	opts: &FormatOptions,
+	theme: &Theme,
+) -> String {
+	let mut output = String::new();
+	// Writing to a `String` never fails.
+	let _ = write_output(&mut output, msg, report, err_msg, opts, theme);
+	output
+}
+
+// Same as `format_output`, but the output is written to `out`.
+pub(crate) fn write_output<W>(
+	out: &mut W,
+	msg: &str,
+	report: &ErrorReport,
+	err_msg: &str,
+	opts: &FormatOptions,
+	theme: &Theme,
+) -> fmt::Result
+where
+	W: fmt::Write + ?Sized,
+{
+	let sep = &opts.separator;
+
+	if let Some(app_name) = &opts.app_name {
+		write!(out, "{app_name}: ")?;
+	}
+	// Errors with a code are always labeled, so the code stands out.
+	// Warnings are always labeled, so they aren't mistaken for errors.
+	let code = report.code.as_deref();
+	if report.severity == Severity::Warning {
+		write_label(out, &opts.strings.warning, code, &theme.warning_label)?;
+	} else if opts.error_label || code.is_some() {
+		write_label(out, &opts.strings.error, code, &theme.label)?;
+	}
+
+	if msg.is_empty() {
+		// There is nothing to separate the error from.
+		return out.write_str(err_msg);
+	}
+
+	if report.primary.is_empty() {
+		// Anything else (e.g. a hint) follows on the next lines.
+		theme.message.write(out, msg)?;
+		out.write_char('\n')?;
+		return out.write_str(err_msg.strip_prefix('\n').unwrap_or(err_msg));
+	}
+
+	match opts.message_position {
+		MessagePosition::Before => {
+			theme.message.write(out, msg)?;
+			// Pad the message based on its visible length, so the
+			// padding isn't affected by the styling.
+			if let Some(min_width) = opts.message_min_width {
+				let width = msg.chars().count();
+				write!(out, "{:pad$}", "", pad = min_width.saturating_sub(width))?;
+			}
+			// err_msg contains a trailing newline so and 
+			// additional newline is omitted here.
+			write!(out, "{sep}{err_msg}")
+		},
+		MessagePosition::After => {
+			// The message goes at the end of the error's first line.
+			let (first, rest) = err_msg.split_once('\n').unwrap_or((err_msg, ""));
+			write!(out, "{first}{sep}")?;
+			theme.message.write(out, msg)?;
+			write!(out, "\n{rest}")
+		},
+	}
+}
+
+// Write the label, e.g. `error:` or `error[<code>]:`, in front of the message.
+fn write_label<W>(out: &mut W, label: &str, code: Option<&str>, style: &Style) -> fmt::Result
+where
+	W: fmt::Write + ?Sized,
+{
+	match code {
+		Some(code) => style.write(out, &format!("{label}[{code}]"))?,
+		None => style.write(out, label)?,
+	}
+	out.write_str(": ")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::{current_config, Writer};
+	use crate::formatter::Formatter;
+	use crate::report::ExampleCommand;
+	use std::io;
+	use std::sync::{Arc, Mutex};
+
+	fn json_config() -> Config {
+		Config {
+			output: OutputMode::Json,
+			..current_config()
+		}
+	}
+
+	#[test]
+	fn json_output_contains_all_segments() {
+		let report = ErrorReport {
+			message: "Failed to read config".to_owned(),
+			code: Some("E013".to_owned()),
+			severity: Severity::Fatal,
+			primary: "permission denied".to_owned(),
+			causes: vec!["os error 13".to_owned()],
+			snippet: None,
+			hints: vec!["Check the file's permissions".to_owned()],
+			auto_hints: 0,
+			examples: vec![ExampleCommand::new("chmod +r config.toml")],
+			notes: vec![],
+			links: vec!["https://example.com/permissions".to_owned()],
+			location: None,
+			debug: None,
+			span_trace: None,
+			backtrace: None,
+			items: vec![ErrorReport {
+				primary: "not readable".to_owned(),
+				..ErrorReport::with_message("config.toml")
+			}],
+		};
+		assert_eq!(
+			format_report(&report, &json_config(), true),
+			concat!(
+				r#"{"message":"Failed to read config","code":"E013","severity":"fatal","error":"permission denied","#,
+				r#""causes":["os error 13"],"snippet":null,"hints":["Check the file's permissions"],"#,
+				r#""examples":[{"label":null,"command":"chmod +r config.toml"}],"notes":[],"links":["https://example.com/permissions"],"location":null,"#,
+				r#""debug":null,"span_trace":null,"backtrace":null,"items":[{"message":"config.toml","code":null,"#,
+				r#""severity":"fatal","error":"not readable","causes":[],"snippet":null,"hints":[],"examples":[],"#,
+				r#""notes":[],"links":[],"location":null,"debug":null,"span_trace":null,"backtrace":null,"items":[]}]}"#,
+				"\n",
+			),
+		);
+	}
+
+	#[test]
+	fn json_strings_are_escaped() {
+		let report = ErrorReport {
+			primary: "\"quoted\" back\\slash\nnew line\ttab\u{1b}".to_owned(),
+			..ErrorReport::with_message("message")
+		};
+		let json = report.to_json();
+		assert!(json.contains(r#""error":"\"quoted\" back\\slash\nnew line\ttab\u001b""#));
+		assert!(json.contains(r#""hints":[]"#));
+	}
+
+	#[test]
+	fn human_output_lists_notes_last() {
+		let config = Config {
+			output: OutputMode::Human,
+			..current_config()
+		};
+		let report = ErrorReport {
+			primary: "error".to_owned(),
+			notes: vec!["note".to_owned()],
+			..ErrorReport::with_message("message")
+		};
+		let expected = format_output("message", &report, "error\nnote: note\n", &config.format, &Theme::plain());
+		assert_eq!(format_report(&report, &config, false), expected);
+	}
+
+	#[test]
+	fn backtraces_are_displayed_last() {
+		let config = current_config();
+		let report = ErrorReport {
+			primary: "error".to_owned(),
+			hints: vec!["hint".to_owned()],
+			backtrace: Some("   0: main\n".to_owned()),
+			..ErrorReport::with_message("message")
+		};
+		let expected = "message: error\nhint\nBacktrace:\n   0: main\n";
+		assert_eq!(format_report(&report, &config, false), expected);
+	}
+
+	#[test]
+	fn quiet_output_has_no_backtrace() {
+		let mut config = current_config();
+		config.format.verbosity = crate::Verbosity::Quiet;
+		let report = ErrorReport {
+			primary: "error".to_owned(),
+			backtrace: Some("   0: main\n".to_owned()),
+			..ErrorReport::with_message("message")
+		};
+		assert_eq!(format_report(&report, &config, false), "message: error\n");
+	}
+
+	#[test]
+	fn reports_can_be_written_to_any_writer() {
+		#[derive(Clone, Default)]
+		struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+		impl io::Write for Buffer {
+			fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+				self.0.lock().unwrap().write(buf)
+			}
+
+			fn flush(&mut self) -> io::Result<()> {
+				Ok(())
+			}
+		}
+
+		let buffer = Buffer::default();
+		let config = Config {
+			writer: Some(Writer::new(buffer.clone())),
+			..current_config()
+		};
+		print_report_with(&ErrorReport::with_message("message"), &config);
+		print_report_with(&ErrorReport::with_message("another message"), &config);
+		let written = buffer.0.lock().unwrap().clone();
+		assert_eq!(String::from_utf8(written).unwrap(), "message\nanother message\n");
+	}
+
+	#[test]
+	fn colored_notes_are_dimmed() {
+		let config = current_config();
+		let report = ErrorReport {
+			notes: vec!["note".to_owned()],
+			..ErrorReport::with_message("message")
+		};
+		let expected = "\x1b[1mmessage\x1b[0m\n\x1b[2mnote: note\x1b[0m\n";
+		assert_eq!(format_report(&report, &config, true), expected);
+	}
+
+	#[test]
+	fn links_are_hyperlinks_if_styled() {
+		let config = current_config();
+		let report = ErrorReport {
+			links: vec!["https://example.com".to_owned()],
+			..ErrorReport::with_message("message")
+		};
+		let plain = format_report(&report, &config, false);
+		assert_eq!(plain, "message\nsee also: https://example.com\n");
+		let styled = format_report(&report, &config, true);
+		let link = "\x1b]8;;https://example.com\x1b\\https://example.com\x1b]8;;\x1b\\";
+		assert_eq!(styled, format!("\x1b[1mmessage\x1b[0m\nsee also: {link}\n"));
+	}
+
+	#[test]
+	fn errors_starting_with_a_new_line_are_separated() {
+		let report = ErrorReport { primary: "\n  error".to_owned(), ..ErrorReport::default() };
+		let output = format_output("message", &report, "\n  error\n", &FormatOptions::default(), &Theme::plain());
+		assert_eq!(output, "message: \n  error\n");
+		let output = format_output("message", &ErrorReport::default(), "\nhint\n", &FormatOptions::default(), &Theme::plain());
+		assert_eq!(output, "message\nhint\n");
+	}
+
+	#[test]
+	fn error_labels_follow_the_app_name() {
+		let opts = FormatOptions {
+			app_name: Some("myapp".into()),
+			error_label: true,
+			..Default::default()
+		};
+		let plain = format_output("Missing configuration file", &ErrorReport::default(), "", &opts, &Theme::plain());
+		assert_eq!(plain, "myapp: error: Missing configuration file\n");
+		let report = ErrorReport { primary: "error".to_owned(), ..ErrorReport::default() };
+		let styled = format_output("message", &report, "error\n", &opts, &Theme::default());
+		assert_eq!(styled, "myapp: \x1b[1;31merror\x1b[0m: \x1b[1mmessage\x1b[0m: error\n");
+	}
+
+	#[test]
+	fn installed_formatters_lay_out_the_output() {
+		struct Upper;
+
+		impl ReportFormatter for Upper {
+			fn format(&self, report: &ErrorReport, opts: &FormatOptions, theme: &Theme) -> String {
+				DefaultFormatter.format(report, opts, theme).to_uppercase()
+			}
+		}
+
+		let mut config = Config {
+			formatter: Some(Formatter::new(Upper)),
+			..current_config()
+		};
+		let report = ErrorReport {
+			primary: "error".to_owned(),
+			..ErrorReport::with_message("message")
+		};
+		assert_eq!(format_report(&report, &config, false), "MESSAGE: ERROR\n");
+		config.formatter = None;
+		assert_eq!(format_report(&report, &config, false), "message: error\n");
+	}
+
+	#[test]
+	fn themes_style_every_part() {
+		let config = Config {
+			theme: Theme::cargo(),
+			..current_config()
+		};
+		let report = ErrorReport {
+			primary: "error".to_owned(),
+			causes: vec!["cause".to_owned()],
+			hints: vec!["hint".to_owned()],
+			examples: vec![ExampleCommand::new("app init")],
+			..ErrorReport::with_message("message")
+		};
+		let expected = "\x1b[1mmessage\x1b[0m: error\n\x1b[1;34mCaused by:\x1b[0m cause\n\x1b[36mhint\x1b[0m: \x1b[1m`app init`\x1b[0m\n";
+		assert_eq!(format_report(&report, &config, true), expected);
+		let config = Config { theme: Theme::plain(), ..config };
+		assert_eq!(format_report(&report, &config, true), format_report(&report, &config, false));
+	}
+
+	#[test]
+	fn lines_are_wrapped_at_the_given_width() {
+		let mut config = current_config();
+		let report = ErrorReport {
+			primary: "the configuration file doesn't exist".to_owned(),
+			hints: vec!["Create a configuration file".to_owned()],
+			..ErrorReport::with_message("message")
+		};
+		let expected = "message: the configuration\n  file doesn't exist\nCreate a configuration file\n";
+		config.format.wrap = Wrap::Width(28);
+		assert_eq!(format_report(&report, &config, false), expected);
+		// Only stderr is wrapped at the width of the terminal.
+		config.format.wrap = Wrap::Terminal;
+		let unwrapped = "message: the configuration file doesn't exist\nCreate a configuration file\n";
+		assert_eq!(format_report(&report, &config, false), unwrapped);
+	}
+
+	#[test]
+	fn strings_can_be_translated() {
+		let mut config = current_config();
+		config.format.strings = crate::Strings {
+			caused_by: "Verursacht durch:".into(),
+			note: "Hinweis:".into(),
+			error: "Fehler".into(),
+			..Default::default()
+		};
+		let report = ErrorReport {
+			code: Some("E012".to_owned()),
+			primary: "error".to_owned(),
+			causes: vec!["cause".to_owned()],
+			notes: vec!["note".to_owned()],
+			..ErrorReport::with_message("message")
+		};
+		let expected = "Fehler[E012]: message: error\nVerursacht durch: cause\nHinweis: note\n";
+		assert_eq!(format_report(&report, &config, false), expected);
+	}
+
+	#[test]
+	fn custom_terminators_get_the_report() {
+		fn unwind(report: &ErrorReport, code: i32) -> ! {
+			std::panic::resume_unwind(Box::new((report.message.clone(), code)))
+		}
+
+		let report = ErrorReport::with_message("message");
+		let payload = std::panic::catch_unwind(|| terminate(Terminator::Custom(unwind), &report, 3)).unwrap_err();
+		assert_eq!(payload.downcast_ref::<(String, i32)>(), Some(&("message".to_owned(), 3)));
+	}
+
+	#[test]
+	fn timestamps_come_first() {
+		let config = Config {
+			timestamps: true,
+			..current_config()
+		};
+		let report = ErrorReport {
+			primary: "error".to_owned(),
+			..ErrorReport::with_message("message")
+		};
+		let output = format_report(&report, &config, true);
+		let (timestamp, rest) = output.split_once(' ').unwrap();
+		assert_eq!((timestamp.len(), &timestamp[10..11], &timestamp[19..]), (20, "T", "Z"));
+		assert_eq!(rest, "\x1b[1mmessage\x1b[0m: error\n");
+	}
+
+	#[test]
+	fn warnings_are_labeled() {
+		let config = current_config();
+		let report = ErrorReport {
+			severity: Severity::Warning,
+			primary: "error".to_owned(),
+			..ErrorReport::with_message("message")
+		};
+		assert_eq!(format_report(&report, &config, false), "warning: message: error\n");
+		let styled = format_report(&report, &config, true);
+		assert_eq!(styled, "\x1b[1;33mwarning\x1b[0m: \x1b[1mmessage\x1b[0m: error\n");
+	}
+
+	#[test]
+	fn codes_are_displayed_in_the_label() {
+		let config = current_config();
+		let report = ErrorReport {
+			code: Some("E012".to_owned()),
+			primary: "error".to_owned(),
+			..ErrorReport::with_message("message")
+		};
+		assert_eq!(format_report(&report, &config, false), "error[E012]: message: error\n");
+		let styled = format_report(&report, &config, true);
+		assert_eq!(styled, "\x1b[1;31merror[E012]\x1b[0m: \x1b[1mmessage\x1b[0m: error\n");
+	}
+}
@@ -0,0 +1,163 @@
+use crate::output::print_report;
+use crate::report::ErrorReport;
+use std::any::Any;
+use std::fmt::Display;
+use std::panic::{self, Location};
+
+/// Display panics the same way as errors passed to
+/// [`giveup`](crate::Giveup::giveup).
+///
+/// `msg` is displayed in front of the panic message. After
+/// the message is displayed, the panic proceeds as usual.
+/// Use [`PanicHook`] to display more than that.
+/// # Example
+/// ```rust
+/// giveup::install_panic_hook("An unexpected error occurred");
+/// ```
+pub fn install_panic_hook<S>(msg: S)
+where
+	S: Display,
+{
+	PanicHook::new(msg).install();
+}
+
+/// Same as [`install_panic_hook`], but the location in the
+/// source code at which the panic occurred is displayed, too.
+pub fn install_panic_hook_with_location<S>(msg: S)
+where
+	S: Display,
+{
+	PanicHook::new(msg).location(true).install();
+}
+
+// Hint displayed if users are asked to report panics.
+const BUG_REPORT_HINT: &str = "This is a bug. Please report it, so it can be fixed";
+
+/// Builder for a panic hook which displays panics the same
+/// way as errors passed to [`giveup`](crate::Giveup::giveup).
+///
+/// Since panics are bugs, the hook can ask users to report them
+/// and point them to the issue tracker.
+/// # Example
+/// ```rust
+/// giveup::PanicHook::new("An unexpected error occurred")
+///     .location(true)
+///     .bug_report_url("https://github.com/thass0/giveup/issues")
+///     .install();
+/// ```
+#[derive(Debug, Clone)]
+#[must_use = "the panic hook only takes effect once `install` is called"]
+pub struct PanicHook {
+	msg: String,
+	show_location: bool,
+	bug_report_url: Option<String>,
+}
+
+impl PanicHook {
+	/// Start building a hook which displays `msg` in front of the panic message.
+	pub fn new<S>(msg: S) -> Self
+	where
+		S: Display,
+	{
+		Self {
+			msg: msg.to_string(),
+			show_location: false,
+			bug_report_url: None,
+		}
+	}
+
+	/// Display the location in the source code at which the panic occurred.
+	pub fn location(mut self, show_location: bool) -> Self {
+		self.show_location = show_location;
+		self
+	}
+
+	/// Ask users to report the panic and link to `url`, e.g. the issue tracker.
+	pub fn bug_report_url<S>(mut self, url: S) -> Self
+	where
+		S: Display,
+	{
+		self.bug_report_url = Some(url.to_string());
+		self
+	}
+
+	/// Replace the current panic hook with this one. After the panic
+	/// is displayed, it proceeds as usual.
+	pub fn install(self) {
+		panic::set_hook(Box::new(move |info| {
+			let location = if self.show_location { info.location() } else { None };
+			print_report(&self.report(info.payload(), location));
+		}));
+	}
+
+	// The report displayed for a panic.
+	fn report(&self, payload: &(dyn Any + Send), location: Option<&Location>) -> ErrorReport {
+		let mut report = format_panic(&self.msg, payload, location);
+		if let Some(url) = &self.bug_report_url {
+			report.hints.push(BUG_REPORT_HINT.to_owned());
+			report.links.push(url.clone());
+		}
+		report
+	}
+}
+
+// Turn a panic into a report like that of an error.
+fn format_panic(msg: &str, payload: &(dyn Any + Send), location: Option<&Location>) -> ErrorReport {
+	ErrorReport {
+		message: msg.to_owned(),
+		primary: payload_message(payload).to_owned(),
+		notes: location.map(|location| format!("Panicked at {location}")).into_iter().collect(),
+		..ErrorReport::default()
+	}
+}
+
+// Panic payloads are strings unless `panic_any` is used.
+pub(crate) fn payload_message(payload: &(dyn Any + Send)) -> &str {
+	if let Some(s) = payload.downcast_ref::<&str>() {
+		s
+	} else if let Some(s) = payload.downcast_ref::<String>() {
+		s.as_str()
+	} else {
+		"Box<dyn Any>"
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::options::FormatOptions;
+
+	#[test]
+	fn string_payloads_are_formatted() {
+		let opts = FormatOptions::default();
+		let report = format_panic("message", &"static payload", None);
+		assert_eq!(format_error_report(&report, &opts), "message: static payload\n");
+		let report = format_panic("message", &String::from("owned payload"), None);
+		assert_eq!(format_error_report(&report, &opts), "message: owned payload\n");
+	}
+
+	#[test]
+	fn other_payloads_are_formatted() {
+		let report = format_panic("message", &42, None);
+		assert_eq!(report.primary, "Box<dyn Any>");
+	}
+
+	#[test]
+	fn locations_are_formatted() {
+		let location = Location::caller();
+		let report = format_panic("message", &"payload", Some(location));
+		let err_msg = report.format_err_msg(&FormatOptions::default());
+		assert_eq!(err_msg, format!("payload\nnote: Panicked at {}:{}:{}\n", file!(), location.line(), location.column()));
+	}
+
+	#[test]
+	fn bug_reports_are_requested() {
+		let hook = PanicHook::new("message").bug_report_url("https://example.com/issues");
+		let err_msg = hook.report(&"payload", None).format_err_msg(&FormatOptions::default());
+		assert_eq!(err_msg, format!("payload\n{BUG_REPORT_HINT}\nsee also: https://example.com/issues\n"));
+	}
+
+	fn format_error_report(report: &ErrorReport, opts: &FormatOptions) -> String {
+		crate::output::format_output(&report.message, report, &report.format_err_msg(opts), opts, &crate::theme::Theme::plain())
+	}
+}
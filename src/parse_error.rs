@@ -0,0 +1,153 @@
+//! Parse errors of file formats like JSON, TOML and YAML,
+//! which point at a line and column of the file.
+
+use crate::giveup::sources;
+use std::error::Error;
+
+// Whether any of the file formats is enabled.
+const ENABLED: bool = cfg!(any(feature = "serde_json", feature = "toml", feature = "serde_yaml"));
+
+// Beginning of the hint added for parse errors.
+const HINT_PREFIX: &str = "Fix the file at line ";
+
+// Parse error with the position taken out of its message.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct ParseError {
+	message: String,
+	line: usize,
+	column: usize,
+}
+
+impl ParseError {
+	// The message on a single line. The position is kept at the
+	// end, so `Snippet::source_file` can still find it.
+	pub(crate) fn flat_message(&self) -> String {
+		format!("{} at line {} column {}", self.message, self.line, self.column)
+	}
+
+	fn hint(&self) -> String {
+		format!("{HINT_PREFIX}{}", self.line)
+	}
+}
+
+// The error given up on can't be downcast, because it doesn't
+// have to be `'static`, so it's recognized by its message.
+pub(crate) fn parse_error(err: &dyn Error) -> Option<ParseError> {
+	if !ENABLED {
+		return None;
+	}
+	message_parse_error(&err.to_string())
+}
+
+// Sources are only taken for parse errors if they are of a known type.
+pub(crate) fn source_parse_error(source: &(dyn Error + 'static)) -> Option<ParseError> {
+	if !is_known(source) {
+		return None;
+	}
+	message_parse_error(&source.to_string())
+}
+
+// The hint for the first parse error in the chain.
+pub(crate) fn parse_hint(err: &dyn Error) -> Option<String> {
+	parse_error(err)
+		.or_else(|| sources(err).into_iter().find_map(source_parse_error))
+		.map(|parse_error| parse_error.hint())
+}
+
+#[allow(unused_variables)]
+fn is_known(err: &(dyn Error + 'static)) -> bool {
+	#[cfg(feature = "serde_json")]
+	if err.is::<serde_json::Error>() {
+		return true;
+	}
+	#[cfg(feature = "toml")]
+	if err.is::<toml::de::Error>() {
+		return true;
+	}
+	#[cfg(feature = "serde_yaml")]
+	if err.is::<serde_yaml::Error>() {
+		return true;
+	}
+	false
+}
+
+// Parse errors of TOML start with `TOML parse error at line 1, column 8`,
+// followed by the line and the message. Those of JSON and YAML read
+// `expected value at line 1 column 8`, possibly followed by more context.
+fn message_parse_error(msg: &str) -> Option<ParseError> {
+	if let Some(rest) = msg.strip_prefix("TOML parse error at line ") {
+		let (line, rest) = leading_number(rest)?;
+		let (column, _) = leading_number(rest.strip_prefix(", column ")?)?;
+		let message = msg.lines().rfind(|line| !line.trim().is_empty())?;
+		return Some(ParseError { message: message.to_owned(), line, column });
+	}
+	let (message, rest) = msg.split_once(" at line ")?;
+	let (line, rest) = leading_number(rest)?;
+	let (column, _) = leading_number(rest.strip_prefix(" column ")?)?;
+	Some(ParseError { message: message.to_owned(), line, column })
+}
+
+fn leading_number(s: &str) -> Option<(usize, &str)> {
+	let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+	Some((s[..end].parse().ok()?, &s[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn positions_are_taken_out_of_messages() {
+		let toml = "TOML parse error at line 2, column 8\n  |\n2 | port = \"eighty\n  |        ^\ninvalid basic string\n";
+		assert_eq!(message_parse_error(toml), Some(ParseError {
+			message: "invalid basic string".to_owned(),
+			line: 2,
+			column: 8,
+		}));
+		let yaml = "did not find expected node content at line 1 column 5, while parsing a flow node";
+		assert_eq!(message_parse_error(yaml).unwrap().flat_message(), "did not find expected node content at line 1 column 5");
+		assert_eq!(message_parse_error("invalid type: string, expected u16"), None);
+	}
+
+	#[cfg(feature = "serde_json")]
+	#[test]
+	fn json_errors_point_at_the_file() {
+		use crate::giveup::GiveupFormatError;
+		let err = serde_json::from_str::<serde_json::Value>("{\"port\": }").unwrap_err();
+		assert_eq!(err.format_err_msg(), "expected value at line 1 column 10\nFix the file at line 1\n");
+	}
+
+	#[cfg(feature = "toml")]
+	#[test]
+	fn toml_errors_are_displayed_on_one_line() {
+		use crate::giveup::GiveupFormatError;
+		let err = toml::from_str::<toml::Table>("[server]\nport = \"eighty\n").unwrap_err();
+		assert_eq!(err.format_err_msg(), "invalid basic string, expected `\"` at line 2 column 15\nFix the file at line 2\n");
+	}
+
+	#[cfg(feature = "serde_yaml")]
+	#[test]
+	fn yaml_errors_are_found_in_the_chain() {
+		use crate::giveup::cause_messages;
+		use std::fmt;
+
+		#[derive(Debug)]
+		struct ConfigError(serde_yaml::Error);
+
+		impl fmt::Display for ConfigError {
+			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("invalid configuration")
+			}
+		}
+
+		impl Error for ConfigError {
+			fn source(&self) -> Option<&(dyn Error + 'static)> {
+				Some(&self.0)
+			}
+		}
+
+		let err = ConfigError(serde_yaml::from_str::<serde_yaml::Value>("ports: [80").unwrap_err());
+		assert_eq!(parse_hint(&err).as_deref(), Some("Fix the file at line 2"));
+		assert_eq!(cause_messages(&err), vec!["did not find expected ',' or ']' at line 2 column 1"]);
+	}
+}
@@ -0,0 +1,95 @@
+//! Offering to run the example command of an error, so hints
+//! can be acted on with a single key press.
+
+use crate::config::Config;
+use crate::report::ErrorReport;
+use crate::shell::Shell;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process::Command;
+
+// Ask whether to run the first example command and run it if the
+// user agrees. Nothing is asked unless someone can answer.
+pub(crate) fn offer_fix(report: &ErrorReport, config: &Config) {
+	let Some(example) = report.examples.first() else {
+		return;
+	};
+	if !config.interactive || config.writer.is_some() || !io::stderr().is_terminal() || !io::stdin().is_terminal() {
+		return;
+	}
+	eprint!("{}", question(&example.command));
+	let _ = io::stderr().flush();
+	let mut answer = String::new();
+	if io::stdin().lock().read_line(&mut answer).is_err() || !confirmed(&answer) {
+		return;
+	}
+	// Examples were picked for this shell, so they run in it.
+	let shell = config.shell.unwrap_or_else(Shell::detect);
+	// The command's own output tells whether it worked.
+	let _ = shell_command(shell, &example.command).status();
+}
+
+fn question(command: &str) -> String {
+	format!("Run `{command}` now? [y/N] ")
+}
+
+// Only an explicit yes runs the command.
+fn confirmed(answer: &str) -> bool {
+	matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+// Run `command` the way the user would in their shell.
+fn shell_command(shell: Shell, command: &str) -> Command {
+	let (program, flags): (_, &[_]) = match shell {
+		Shell::Bash => ("bash", &["-c"]),
+		Shell::Zsh => ("zsh", &["-c"]),
+		Shell::Fish => ("fish", &["-c"]),
+		Shell::PowerShell if cfg!(windows) => ("powershell", &["-NoProfile", "-Command"]),
+		Shell::PowerShell => ("pwsh", &["-NoProfile", "-Command"]),
+		Shell::Cmd => return cmd_command(command),
+	};
+	let mut shell = Command::new(program);
+	shell.args(flags).arg(command);
+	shell
+}
+
+// `cmd` doesn't parse its arguments the way `Command` quotes
+// them, so the command is passed as it is.
+#[cfg(windows)]
+fn cmd_command(command: &str) -> Command {
+	use std::os::windows::process::CommandExt;
+	let mut shell = Command::new("cmd");
+	shell.arg("/C").raw_arg(command);
+	shell
+}
+
+#[cfg(not(windows))]
+fn cmd_command(command: &str) -> Command {
+	let mut shell = Command::new("cmd");
+	shell.arg("/C").arg(command);
+	shell
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn only_yes_is_confirmation() {
+		assert!(confirmed("y\n"));
+		assert!(confirmed(" YES \n"));
+		assert!(!confirmed("\n"));
+		assert!(!confirmed("no\n"));
+		assert_eq!(question("touch config.toml"), "Run `touch config.toml` now? [y/N] ");
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn commands_run_in_the_shell() {
+		// `[[` is only understood by bash itself, not by POSIX `sh`.
+		let status = shell_command(Shell::Bash, "[[ -n \"$HOME\" ]] && exit 3").status().unwrap();
+		assert_eq!(status.code(), Some(3));
+		let fish = shell_command(Shell::Fish, "set -x A 1");
+		assert_eq!(fish.get_program(), "fish");
+		assert_eq!(fish.get_args().collect::<Vec<_>>(), ["-c", "set -x A 1"]);
+	}
+}
@@ -0,0 +1,165 @@
+use crate::report::{ErrorReport, ExampleCommand};
+use std::fmt;
+use std::sync::Arc;
+
+/// Function applied to all text of a report before it's displayed.
+#[derive(Clone)]
+pub(crate) struct Redactor(Arc<dyn Fn(&str) -> String + Send + Sync>);
+
+impl Redactor {
+	pub(crate) fn new<F>(redact: F) -> Self
+	where
+		F: Fn(&str) -> String + Send + Sync + 'static,
+	{
+		Self(Arc::new(redact))
+	}
+
+	pub(crate) fn redact_text(&self, text: &str) -> String {
+		(self.0)(text)
+	}
+
+	/// The report with every message, cause, hint, etc. redacted.
+	pub(crate) fn redact(&self, report: &ErrorReport) -> ErrorReport {
+		let redact = |text: &String| self.redact_text(text);
+		let redact_all = |texts: &Vec<String>| texts.iter().map(redact).collect();
+		ErrorReport {
+			message: redact(&report.message),
+			code: report.code.clone(),
+			severity: report.severity,
+			primary: redact(&report.primary),
+			causes: redact_all(&report.causes),
+			snippet: report.snippet.as_ref().map(redact),
+			hints: redact_all(&report.hints),
+			auto_hints: report.auto_hints,
+			examples: report.examples.iter()
+				.map(|example| ExampleCommand {
+					label: example.label.as_ref().map(redact),
+					command: redact(&example.command),
+				})
+				.collect(),
+			notes: redact_all(&report.notes),
+			links: redact_all(&report.links),
+			location: report.location.clone(),
+			debug: report.debug.as_ref().map(redact),
+			span_trace: report.span_trace.as_ref().map(redact),
+			backtrace: report.backtrace.clone(),
+			items: report.items.iter().map(|item| self.redact(item)).collect(),
+		}
+	}
+}
+
+impl fmt::Debug for Redactor {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("Redactor(..)")
+	}
+}
+
+/// Replace the user's home directory with `~`, so paths in
+/// errors don't reveal the user name.
+///
+/// Pass it to [`ConfigBuilder::redact`](crate::ConfigBuilder::redact).
+/// # Example
+/// ```rust
+/// giveup::config()
+///     .redact(giveup::redact_home_dir)
+///     .apply();
+/// ```
+pub fn redact_home_dir(text: &str) -> String {
+	match std::env::var("HOME") {
+		Ok(home) if home.len() > 1 => replace_dir(text, home.trim_end_matches('/'), "~"),
+		_ => text.to_owned(),
+	}
+}
+
+/// Shorten paths in errors by making those within the current
+/// directory relative (e.g. `./src/main.rs`) and replacing the
+/// user's home directory with `~`.
+///
+/// This makes reports shorter, and screenshots users paste into
+/// issues don't reveal their user name. Pass it to
+/// [`ConfigBuilder::redact`](crate::ConfigBuilder::redact).
+/// # Example
+/// ```rust
+/// giveup::config()
+///     .redact(giveup::shorten_paths)
+///     .apply();
+/// ```
+pub fn shorten_paths(text: &str) -> String {
+	let current_dir = std::env::current_dir().ok();
+	let current_dir = current_dir.as_ref().and_then(|dir| dir.to_str());
+	shorten_paths_in(text, current_dir, std::env::var("HOME").ok().as_deref())
+}
+
+// The current directory comes first, since it's usually
+// within the home directory.
+fn shorten_paths_in(text: &str, current_dir: Option<&str>, home: Option<&str>) -> String {
+	let mut shortened = text.to_owned();
+	for (dir, replacement) in [(current_dir, "."), (home, "~")] {
+		if let Some(dir) = dir.filter(|dir| dir.len() > 1) {
+			shortened = replace_dir(&shortened, dir.trim_end_matches('/'), replacement);
+		}
+	}
+	shortened
+}
+
+// Replace `dir` where it's a whole path on its own or followed by a
+// separator, so `/home/ann` doesn't match `/home/anna`, `/data/home/ann`
+// or `https://example.com/home/ann`.
+fn replace_dir(text: &str, dir: &str, replacement: &str) -> String {
+	let is_path_char = |c: char| c.is_alphanumeric() || "_-.~/:".contains(c);
+	let mut redacted = String::with_capacity(text.len());
+	let mut rest = text;
+	while let Some(idx) = rest.find(dir) {
+		let after = &rest[idx + dir.len()..];
+		let before = &text[..text.len() - rest.len() + idx];
+		redacted.push_str(&rest[..idx]);
+		let starts_path = !before.ends_with(is_path_char);
+		let ends_dir = !after.starts_with(|c: char| c.is_alphanumeric() || "_-.".contains(c));
+		if starts_path && ends_dir {
+			redacted.push_str(replacement);
+		} else {
+			redacted.push_str(dir);
+		}
+		rest = after;
+	}
+	redacted.push_str(rest);
+	redacted
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn all_text_is_redacted() {
+		let redactor = Redactor::new(|text| text.replace("hunter2", "***"));
+		let report = ErrorReport {
+			primary: "bad password hunter2".to_owned(),
+			hints: vec!["hunter2 is too short".to_owned()],
+			examples: vec![ExampleCommand::new("login --password hunter2")],
+			..ErrorReport::with_message("Failed to log in with hunter2")
+		};
+		let redacted = redactor.redact(&report);
+		assert_eq!(redacted.message, "Failed to log in with ***");
+		assert_eq!(redacted.primary, "bad password ***");
+		assert_eq!(redacted.hints, vec!["*** is too short".to_owned()]);
+		assert_eq!(redacted.examples[0].command, "login --password ***");
+	}
+
+	#[test]
+	fn paths_are_shortened() {
+		let text = "/home/ann/app/src/main.rs and /home/ann/.config/app.toml";
+		let shortened = shorten_paths_in(text, Some("/home/ann/app"), Some("/home/ann/"));
+		assert_eq!(shortened, "./src/main.rs and ~/.config/app.toml");
+		assert_eq!(shorten_paths_in(text, Some("/"), None), text);
+		let text = "/data/app/x, https://example.com/app/x and '/app/x'";
+		let shortened = shorten_paths_in(text, Some("/app"), None);
+		assert_eq!(shortened, "/data/app/x, https://example.com/app/x and './x'");
+	}
+
+	#[test]
+	fn only_whole_directories_are_replaced() {
+		let text = "/home/ann/a.toml, /home/anna/b.toml and /home/ann";
+		assert_eq!(replace_dir(text, "/home/ann", "~"), "~/a.toml, /home/anna/b.toml and ~");
+	}
+}
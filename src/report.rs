@@ -0,0 +1,465 @@
+use crate::giveup::GiveupFormatError;
+use crate::output::format_output;
+use crate::options::{format_options, FormatOptions, Verbosity};
+use crate::theme::Theme;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::fmt;
+
+/// Structured form of the message displayed by
+/// [`giveup`](crate::Giveup::giveup).
+///
+/// Every part of the message is kept separately, so embedders
+/// (e.g. TUI error panels) can lay them out however they like.
+#[derive(Debug, Clone, Default)]
+pub struct ErrorReport {
+	/// The message passed to `giveup`.
+	pub message: String,
+	/// Short code identifying the error, e.g. `E012`, which is
+	/// displayed as `error[E012]:` in front of the message.
+	pub code: Option<String>,
+	/// How severe the error is.
+	pub severity: Severity,
+	/// The message of the error itself.
+	pub primary: String,
+	/// The messages of the error's sources, starting with the
+	/// direct source of the error.
+	pub causes: Vec<String>,
+	/// The line of a file the error is about, with a caret under the
+	/// column, as added by [`snippet`](crate::Snippet::snippet).
+	pub snippet: Option<String>,
+	/// The hints added to the error, starting with the first one.
+	pub hints: Vec<String>,
+	/// How many of the first `hints` were added automatically, e.g. for
+	/// I/O errors. A hint given by hand replaces them.
+	pub auto_hints: usize,
+	/// The example commands added to the hints.
+	pub examples: Vec<ExampleCommand>,
+	/// Background information displayed after the hints,
+	/// each prefixed with `note:`.
+	pub notes: Vec<String>,
+	/// URLs of documentation about the error.
+	pub links: Vec<String>,
+	/// Where in the source code the error was hinted or given up on,
+	/// e.g. `src/main.rs:42`.
+	///
+	/// Only set with [`Verbosity::Verbose`], so maintainers can find
+	/// where a reported message comes from.
+	pub location: Option<String>,
+	/// The `Debug` representation of the error.
+	///
+	/// Only set with [`Verbosity::Verbose`] and if the primary message
+	/// isn't formatted using `Debug` already
+	/// (see [`prefer_debug`](FormatOptions::prefer_debug)).
+	pub debug: Option<String>,
+	/// The spans which were entered when the error was given up on,
+	/// starting with the innermost one.
+	///
+	/// Only captured with the `tracing` feature and [`Verbosity::Verbose`],
+	/// if the subscriber has a `tracing_error::ErrorLayer`.
+	pub span_trace: Option<String>,
+	/// Backtrace of where the error was hinted or given up on.
+	///
+	/// Only captured if backtraces are enabled with `RUST_BACKTRACE`,
+	/// `RUST_LIB_BACKTRACE` or [`ConfigBuilder::backtrace`](crate::ConfigBuilder::backtrace).
+	pub backtrace: Option<String>,
+	/// Errors reported together, e.g. those of a
+	/// [`MultiError`](crate::MultiError), which are displayed as a
+	/// numbered list below the error. The `message` of each is
+	/// displayed in front of it, e.g. the file the error is about.
+	pub items: Vec<ErrorReport>,
+}
+
+/// How severe a reported error is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Severity {
+	/// Something the program works around, displayed with a
+	/// `warning:` label by [`warn`](crate::Giveup::warn).
+	Warning,
+	/// An error the program recovers from, as displayed by
+	/// [`complain`](crate::Giveup::complain).
+	Error,
+	/// An error which terminates the program (the default).
+	#[default]
+	Fatal,
+}
+
+impl Severity {
+	/// Name of the severity as used in JSON output.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Severity::Warning => "warning",
+			Severity::Error => "error",
+			Severity::Fatal => "fatal",
+		}
+	}
+}
+
+/// Example command displayed with the hints of an error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExampleCommand {
+	/// Description of what the command does differently
+	/// from the others, e.g. `with defaults`.
+	pub label: Option<String>,
+	/// The command itself.
+	pub command: String,
+}
+
+impl ExampleCommand {
+	/// Create an example command without a label.
+	pub fn new<S>(command: S) -> Self
+	where
+		S: fmt::Display,
+	{
+		Self {
+			label: None,
+			command: command.to_string(),
+		}
+	}
+
+	/// Create an example command with a label.
+	pub fn labeled<L, S>(label: L, command: S) -> Self
+	where
+		L: fmt::Display,
+		S: fmt::Display,
+	{
+		Self {
+			label: Some(label.to_string()),
+			command: command.to_string(),
+		}
+	}
+}
+
+impl fmt::Display for ExampleCommand {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match &self.label {
+			Some(label) => write!(f, "{label}: `{}`", self.command),
+			None => write!(f, "`{}`", self.command),
+		}
+	}
+}
+
+impl ErrorReport {
+	// Report that consists of nothing but the message passed to `giveup`.
+	pub(crate) fn with_message(msg: &str) -> Self {
+		Self {
+			message: msg.to_owned(),
+			..Self::default()
+		}
+	}
+
+	// The message and the error on a single line, as items
+	// are displayed in lists.
+	pub(crate) fn item_line(&self) -> String {
+		if self.message.is_empty() {
+			self.primary.clone()
+		} else {
+			format!("{}: {}", self.message, self.primary)
+		}
+	}
+
+	/// Format everything except `message` the same way
+	/// [`format_err_msg`](GiveupFormatError::format_err_msg) does.
+	pub(crate) fn format_err_msg(&self, opts: &FormatOptions) -> String {
+		self.format_err_msg_styled(opts, &Theme::plain())
+	}
+
+	// The causes as they are displayed, with repetitions collapsed if
+	// `dedupe_causes` is set, and the number of causes left out.
+	pub(crate) fn displayed_causes(&self, opts: &FormatOptions) -> (Vec<String>, usize) {
+		let mut causes: Vec<String> = if opts.dedupe_causes {
+			let mut counted: Vec<(&String, usize)> = Vec::new();
+			for cause in &self.causes {
+				match counted.last_mut() {
+					Some((last, count)) if *last == cause => *count += 1,
+					_ => counted.push((cause, 1)),
+				}
+			}
+			counted.into_iter()
+				.map(|(cause, count)| match count {
+					1 => cause.clone(),
+					count => format!("{cause} (\u{d7}{count})"),
+				})
+				.collect()
+		} else {
+			self.causes.clone()
+		};
+
+		let limit = opts.max_causes.filter(|_| opts.verbosity != Verbosity::Verbose);
+		let hidden = limit.map_or(0, |limit| causes.len().saturating_sub(limit));
+		causes.truncate(causes.len() - hidden);
+		(causes, hidden)
+	}
+
+	// Same as `format_err_msg`, but styled using `theme`.
+	pub(crate) fn format_err_msg_styled(&self, opts: &FormatOptions, theme: &Theme) -> String {
+		let mut err_msg = format!("{}\n", self.primary);
+		// Writing to a `String` never fails.
+		let _ = self.write_details(&mut err_msg, opts, theme);
+		err_msg
+	}
+
+	// Write everything below the first line of `format_err_msg_styled`
+	// to `out`, so long reports don't have to be collected first.
+	pub(crate) fn write_details<W>(&self, out: &mut W, opts: &FormatOptions, theme: &Theme) -> fmt::Result
+	where
+		W: fmt::Write + ?Sized,
+	{
+		// Quiet output is made up of a single line.
+		if opts.verbosity == Verbosity::Quiet {
+			return Ok(());
+		}
+
+		// Everything below the first line of an item lines up with its error.
+		for (idx, item) in self.items.iter().enumerate() {
+			let number = format!("{}. ", idx + 1);
+			let mut item_msg = format!("{}\n", item.item_line());
+			item.write_details(&mut item_msg, opts, theme)?;
+			let indent = " ".repeat(number.len());
+			writeln!(out, "{number}{}", item_msg.trim_end().replace('\n', &format!("\n{indent}")))?;
+		}
+
+		let strings = &opts.strings;
+
+		// Verbose output leaves nothing out.
+		if opts.show_causes || opts.verbosity == Verbosity::Verbose {
+			let (causes, hidden) = self.displayed_causes(opts);
+			for cause in causes {
+				theme.cause_prefix.write(out, &strings.caused_by)?;
+				writeln!(out, " {cause}")?;
+			}
+			if hidden > 0 {
+				writeln!(out, "{}", more_causes(hidden))?;
+			}
+		}
+
+		if let Some(snippet) = &self.snippet {
+			writeln!(out, "{}", snippet.trim_end())?;
+		}
+
+		let example = |example: &ExampleCommand| example.to_string();
+		match (self.hints.as_slice(), self.examples.as_slice()) {
+			([h], []) => {
+				theme.hint.write(out, h)?;
+				out.write_char('\n')?;
+			},
+			// Labeled commands are always listed, so the label isn't
+			// mistaken for part of the hint.
+			([h], [e]) if e.label.is_none() => {
+				theme.hint.write(out, h)?;
+				out.write_str(": ")?;
+				theme.example.write(out, &example(e))?;
+				out.write_char('\n')?;
+			},
+			([], [e]) => {
+				theme.example.write(out, &example(e))?;
+				out.write_char('\n')?;
+			},
+			(hints, examples) => {
+				// Multiple hints are displayed as a bulleted list and multiple
+				// commands are listed on their own lines below the hints.
+				match hints {
+					[h] => {
+						theme.hint.write(out, h)?;
+						out.write_str(":\n")?;
+					},
+					hints => {
+						for h in hints {
+							out.write_str("- ")?;
+							theme.hint.write(out, h)?;
+							out.write_char('\n')?;
+						}
+					},
+				}
+				for e in examples {
+					out.write_str("    ")?;
+					theme.example.write(out, &example(e))?;
+					out.write_char('\n')?;
+				}
+			},
+		}
+
+		for note in &self.notes {
+			theme.note.write(out, &format!("{} {note}", strings.note))?;
+			out.write_char('\n')?;
+		}
+
+		for link in &self.links {
+			if theme.hyperlinks {
+				// OSC 8 hyperlink with the URL as its text.
+				writeln!(out, "{} \x1b]8;;{link}\x1b\\{link}\x1b]8;;\x1b\\", strings.see_also)?;
+			} else {
+				writeln!(out, "{} {link}", strings.see_also)?;
+			}
+		}
+
+		if let Some(location) = &self.location {
+			theme.note.write(out, &format!("{} {location}", strings.location))?;
+			out.write_char('\n')?;
+		}
+
+		if let Some(debug) = &self.debug {
+			writeln!(out, "{}\n{}", strings.debug, debug.trim_end())?;
+		}
+
+		if let Some(span_trace) = &self.span_trace {
+			writeln!(out, "{}\n{}", strings.span_trace, span_trace.trim_end())?;
+		}
+
+		if let Some(backtrace) = &self.backtrace {
+			writeln!(out, "{}\n{}", strings.backtrace, backtrace.trim_end())?;
+		}
+
+		Ok(())
+	}
+
+	/// Serialize the report as a single-line JSON object.
+	///
+	/// This is what is displayed when the output mode is set to
+	/// [`OutputMode::Json`](crate::OutputMode::Json). Missing
+	/// text is represented as `null`.
+	///
+	/// # Example
+	/// ```rust
+	/// use giveup::ErrorReport;
+	/// let report = ErrorReport {
+	///     message: "Failed to read config".to_owned(),
+	///     primary: "No such file".to_owned(),
+	///     ..ErrorReport::default()
+	/// };
+	/// assert_eq!(
+	///     report.to_json(),
+	///     r#"{"message":"Failed to read config","code":null,"severity":"fatal","error":"No such file","causes":[],"snippet":null,"hints":[],"examples":[],"notes":[],"links":[],"location":null,"debug":null,"span_trace":null,"backtrace":null,"items":[]}"#,
+	/// );
+	/// ```
+	pub fn to_json(&self) -> String {
+		let mut json = String::from("{\"message\":");
+		push_json_str(&mut json, &self.message);
+		json.push_str(",\"code\":");
+		push_json_opt(&mut json, self.code.as_deref());
+		json.push_str(",\"severity\":");
+		push_json_str(&mut json, self.severity.as_str());
+		json.push_str(",\"error\":");
+		push_json_str(&mut json, &self.primary);
+		json.push_str(",\"causes\":");
+		push_json_array(&mut json, &self.causes);
+		json.push_str(",\"snippet\":");
+		push_json_opt(&mut json, self.snippet.as_deref());
+		json.push_str(",\"hints\":");
+		push_json_array(&mut json, &self.hints);
+		json.push_str(",\"examples\":[");
+		for (i, example) in self.examples.iter().enumerate() {
+			if i > 0 {
+				json.push(',');
+			}
+			json.push_str("{\"label\":");
+			push_json_opt(&mut json, example.label.as_deref());
+			json.push_str(",\"command\":");
+			push_json_str(&mut json, &example.command);
+			json.push('}');
+		}
+		json.push(']');
+		json.push_str(",\"notes\":");
+		push_json_array(&mut json, &self.notes);
+		json.push_str(",\"links\":");
+		push_json_array(&mut json, &self.links);
+		json.push_str(",\"location\":");
+		push_json_opt(&mut json, self.location.as_deref());
+		json.push_str(",\"debug\":");
+		push_json_opt(&mut json, self.debug.as_deref());
+		json.push_str(",\"span_trace\":");
+		push_json_opt(&mut json, self.span_trace.as_deref());
+		json.push_str(",\"backtrace\":");
+		push_json_opt(&mut json, self.backtrace.as_deref());
+		json.push_str(",\"items\":[");
+		for (i, item) in self.items.iter().enumerate() {
+			if i > 0 {
+				json.push(',');
+			}
+			json.push_str(&item.to_json());
+		}
+		json.push_str("]}");
+		json
+	}
+}
+
+// Trailer displayed in place of the causes which were left out.
+pub(crate) fn more_causes(hidden: usize) -> String {
+	let causes = if hidden == 1 { "cause" } else { "causes" };
+	format!("\u{2026}and {hidden} more {causes} (run with --verbose)")
+}
+
+// The text of `backtrace` if it was actually captured.
+pub(crate) fn backtrace_string(backtrace: &Backtrace) -> Option<String> {
+	match backtrace.status() {
+		BacktraceStatus::Captured => Some(backtrace.to_string()),
+		_ => None,
+	}
+}
+
+fn push_json_opt(json: &mut String, s: Option<&str>) {
+	match s {
+		Some(s) => push_json_str(json, s),
+		None => json.push_str("null"),
+	}
+}
+
+fn push_json_array(json: &mut String, items: &[String]) {
+	json.push('[');
+	for (i, item) in items.iter().enumerate() {
+		if i > 0 {
+			json.push(',');
+		}
+		push_json_str(json, item);
+	}
+	json.push(']');
+}
+
+fn push_json_str(json: &mut String, s: &str) {
+	json.push('"');
+	for c in s.chars() {
+		match c {
+			'"' => json.push_str("\\\""),
+			'\\' => json.push_str("\\\\"),
+			'\n' => json.push_str("\\n"),
+			'\r' => json.push_str("\\r"),
+			'\t' => json.push_str("\\t"),
+			c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+			c => json.push(c),
+		}
+	}
+	json.push('"');
+}
+
+/// Split the message `giveup` would display for `err` into its segments.
+///
+/// The error is formatted using the options set with
+/// [`set_format_options`](crate::set_format_options).
+///
+/// # Example
+/// ```rust
+/// use giveup::{format_segments, Giveup};
+/// let err = "x".parse::<u8>()
+///     .hint("Pass a number")
+///     .unwrap_err();
+/// let report = format_segments(&err, "Invalid argument");
+/// assert_eq!(report.message, "Invalid argument");
+/// assert_eq!(report.hints, vec!["Pass a number"]);
+/// ```
+pub fn format_segments<E>(err: &E, msg: &str) -> ErrorReport
+where
+	E: GiveupFormatError,
+{
+	let mut report = err.segments(&format_options());
+	report.message = msg.to_owned();
+	report
+}
+
+/// Format the whole message `giveup` would display for `err`
+/// using the given options, but without any styling.
+pub fn format_error<E>(err: &E, msg: &str, opts: &FormatOptions) -> String
+where
+	E: GiveupFormatError,
+{
+	let report = err.segments(opts);
+	format_output(msg, &report, &report.format_err_msg(opts), opts, &Theme::plain())
+}
@@ -0,0 +1,115 @@
+use crate::giveup::{with_caller_context, GiveupFormatError};
+use crate::output::exit_gracefully;
+use crate::report::format_segments;
+use std::fmt::Display;
+
+/// Call `f` until it succeeds and terminate the program gracefully
+/// if it fails `attempts` times in a row.
+///
+/// The displayed message contains the error of the last attempt
+/// and how many attempts were made. `f` is called at least once,
+/// even if `attempts` is zero.
+/// # Example
+/// ```rust
+/// use giveup::retry_or_giveup;
+/// let mut tries = 0;
+/// let answer = retry_or_giveup(3, || {
+///     tries += 1;
+///     if tries < 2 { "x".parse::<u8>() } else { "42".parse::<u8>() }
+/// }, "Failed to get the answer");
+/// assert_eq!(answer, 42);
+/// ```
+#[track_caller]
+pub fn retry_or_giveup<T, E, F, M>(attempts: usize, f: F, msg: M) -> T
+where
+	F: FnMut() -> Result<T, E>,
+	E: GiveupFormatError,
+	M: Display,
+{
+	retry_or_giveup_with_backoff(attempts, f, |_| {}, msg)
+}
+
+/// Same as [`retry_or_giveup`], but `backoff` is called between attempts
+/// with the number of failed attempts so far (e.g. to sleep for a while).
+#[track_caller]
+pub fn retry_or_giveup_with_backoff<T, E, F, B, M>(
+	attempts: usize,
+	mut f: F,
+	mut backoff: B,
+	msg: M,
+) -> T
+where
+	F: FnMut() -> Result<T, E>,
+	B: FnMut(usize),
+	E: GiveupFormatError,
+	M: Display,
+{
+	let attempts = attempts.max(1);
+	let mut failed = 0;
+	loop {
+		match f() {
+			Ok(t) => return t,
+			Err(e) => {
+				failed += 1;
+				if failed == attempts {
+					let mut report = with_caller_context(format_segments(&e, &msg.to_string()));
+					report.notes.push(format!(
+						"Gave up after {attempts} attempt{}",
+						if attempts == 1 { "" } else { "s" },
+					));
+					exit_gracefully(&report, e.exit_code())
+				}
+				backoff(failed);
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::capture;
+	use std::num::ParseIntError;
+
+	#[test]
+	fn the_first_success_is_returned() {
+		let mut calls = 0;
+		let mut backoffs = Vec::new();
+		let result = retry_or_giveup_with_backoff(5, || {
+			calls += 1;
+			if calls < 3 { "x".parse::<u8>() } else { "42".parse::<u8>() }
+		}, |failed| backoffs.push(failed), "message");
+		assert_eq!(result, 42);
+		assert_eq!(calls, 3);
+		assert_eq!(backoffs, vec![1, 2]);
+	}
+
+	#[test]
+	fn the_last_error_is_displayed_after_all_attempts_failed() {
+		let mut calls = 0;
+		let captured = capture(|| {
+			retry_or_giveup(3, || {
+				calls += 1;
+				Err::<(), ParseIntError>("x".parse::<u8>().unwrap_err())
+			}, "message");
+		});
+		assert_eq!(calls, 3);
+		assert_eq!(captured, vec![(
+			"message: invalid digit found in string\nnote: Gave up after 3 attempts\n".to_owned(),
+			1,
+		)]);
+	}
+
+	#[test]
+	fn zero_attempts_still_call_once() {
+		let mut calls = 0;
+		let captured = capture(|| {
+			retry_or_giveup(0, || {
+				calls += 1;
+				"x".parse::<u8>()
+			}, "message");
+		});
+		assert_eq!(calls, 1);
+		assert_eq!(captured[0].0, "message: invalid digit found in string\nnote: Gave up after 1 attempt\n");
+	}
+}
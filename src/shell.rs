@@ -0,0 +1,145 @@
+use crate::config::current_config;
+use crate::hint::{Hint, HintedError};
+use crate::report::ExampleCommand;
+use std::fmt::Display;
+
+/// Shell the user runs example commands in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+	/// Bash and other POSIX shells like `sh` and `dash`.
+	Bash,
+	/// The Z shell.
+	Zsh,
+	/// The friendly interactive shell.
+	Fish,
+	/// Windows PowerShell and PowerShell Core.
+	PowerShell,
+	/// The Windows command prompt.
+	Cmd,
+}
+
+impl Shell {
+	/// The user's shell based on `SHELL`, or on Windows whether
+	/// `PSModulePath` is set. Defaults to Bash on Unix and
+	/// PowerShell on Windows.
+	pub fn detect() -> Self {
+		if let Some(shell) = std::env::var_os("SHELL") {
+			let name = std::path::Path::new(&shell).file_stem().unwrap_or_default();
+			match name.to_string_lossy().as_ref() {
+				"zsh" => return Shell::Zsh,
+				"fish" => return Shell::Fish,
+				"pwsh" | "powershell" => return Shell::PowerShell,
+				"bash" | "sh" | "dash" | "ksh" => return Shell::Bash,
+				_ => {},
+			}
+		}
+		if cfg!(windows) && std::env::var_os("PSModulePath").is_none() {
+			Shell::Cmd
+		} else if cfg!(windows) {
+			Shell::PowerShell
+		} else {
+			Shell::Bash
+		}
+	}
+
+	// Bash and zsh understand each other's commands.
+	fn accepts(&self, shell: Shell) -> bool {
+		*self == shell || matches!((self, shell), (Shell::Bash, Shell::Zsh) | (Shell::Zsh, Shell::Bash))
+	}
+}
+
+/// Add example commands which are only displayed in one shell,
+/// for commands whose syntax depends on the shell.
+pub trait ShellExample {
+	/// Consumes and returns `self` with an example command which is
+	/// only displayed if the user runs `shell` (see [`Shell::detect`]).
+	///
+	/// A command for Bash is also displayed in zsh and vice versa, but
+	/// a command written for the shell itself takes precedence. Use
+	/// [`ConfigBuilder::shell`](crate::ConfigBuilder::shell) to pick
+	/// the shell instead.
+	/// # Example
+	/// ```rust
+	/// use giveup::{Giveup, Shell, ShellExample};
+	/// let token = std::env::var("CARGO")
+	///     .hint("Set the API token")
+	///     .shell_example(Shell::Bash, "export TOKEN=<token>")
+	///     .shell_example(Shell::Fish, "set -x TOKEN <token>")
+	///     .shell_example(Shell::PowerShell, "$env:TOKEN = \"<token>\"")
+	///     .shell_example(Shell::Cmd, "set TOKEN=<token>")
+	///     .giveup("Missing API token");
+	/// ```
+	fn shell_example<S>(self, shell: Shell, example: S) -> Self
+	where
+		S: Display;
+}
+
+impl<T, E> ShellExample for Result<T, HintedError<E>> {
+	fn shell_example<S>(mut self, shell: Shell, example: S) -> Self
+	where
+		S: Display,
+	{
+		if let Err(ref mut e) = self {
+			e.hint.shell_examples.push((shell, ExampleCommand::new(example.to_string())));
+		}
+		self
+	}
+}
+
+impl ShellExample for Hint {
+	fn shell_example<S>(mut self, shell: Shell, example: S) -> Self
+	where
+		S: Display,
+	{
+		self.shell_examples.push((shell, ExampleCommand::new(example.to_string())));
+		self
+	}
+}
+
+// The examples for the user's shell.
+pub(crate) fn examples_for_shell(examples: &[(Shell, ExampleCommand)]) -> Vec<ExampleCommand> {
+	if examples.is_empty() {
+		return Vec::new();
+	}
+	let shell = current_config().shell.unwrap_or_else(Shell::detect);
+	let exact: Vec<_> = examples.iter()
+		.filter(|(example_shell, _)| *example_shell == shell)
+		.map(|(_, example)| example.clone())
+		.collect();
+	if !exact.is_empty() {
+		return exact;
+	}
+	examples.iter()
+		.filter(|(example_shell, _)| shell.accepts(*example_shell))
+		.map(|(_, example)| example.clone())
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn similar_shells_share_examples() {
+		assert!(Shell::Zsh.accepts(Shell::Bash));
+		assert!(!Shell::Fish.accepts(Shell::Bash));
+		assert!(!Shell::PowerShell.accepts(Shell::Cmd));
+	}
+
+	#[test]
+	fn examples_for_the_shell_itself_come_first() {
+		let examples = [
+			(Shell::Bash, ExampleCommand::new("export A=1")),
+			(Shell::Zsh, ExampleCommand::new("typeset -x A=1")),
+			(Shell::Fish, ExampleCommand::new("set -x A 1")),
+		];
+		let shell = current_config().shell.unwrap_or_else(Shell::detect);
+		let displayed = examples_for_shell(&examples);
+		match shell {
+			Shell::Bash => assert_eq!(displayed, vec![ExampleCommand::new("export A=1")]),
+			Shell::Fish => assert_eq!(displayed, vec![ExampleCommand::new("set -x A 1")]),
+			Shell::Zsh => assert_eq!(displayed, vec![ExampleCommand::new("typeset -x A=1")]),
+			Shell::PowerShell | Shell::Cmd => assert!(displayed.is_empty()),
+		}
+	}
+}
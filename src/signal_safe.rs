@@ -0,0 +1,138 @@
+//! Output which can be written from signal handlers.
+//!
+//! The message is formatted into a buffer on the stack and handed to
+//! `write(2)` directly. Nothing is allocated and neither the configuration
+//! nor stderr are locked, so the configuration and exit hooks don't apply.
+
+use std::fmt::{self, Display, Write};
+
+// Longer output is cut off.
+const BUFFER_SIZE: usize = 1024;
+
+/// Display `msg` and exit with `code` from contexts like signal
+/// handlers, where [`giveup`](crate::giveup) can deadlock or allocate.
+///
+/// Only the message is displayed the way `giveup` displays it without
+/// any configuration, cut off after 1023 bytes. It's written to stderr
+/// with `write(2)` and the process is ended with `_exit(2)`. This is
+/// async-signal-safe as long as the `Display` implementation of `msg`
+/// doesn't allocate, like the ones of `&str` and the integer types.
+///
+/// ```rust,no_run
+/// // E.g. called from a `SIGTERM` handler.
+/// extern "C" fn on_sigterm(_: i32) {
+///     giveup::giveup_signal_safe("Interrupted while writing the archive", 130);
+/// }
+/// ```
+pub fn giveup_signal_safe<M>(msg: M, code: i32) -> !
+where
+	M: Display,
+{
+	let mut buffer = StackBuffer::new();
+	buffer.write_line(msg);
+	raw::write_stderr(buffer.as_bytes());
+	raw::exit(code)
+}
+
+// Fixed-size buffer which silently drops what doesn't fit.
+struct StackBuffer {
+	bytes: [u8; BUFFER_SIZE],
+	len: usize,
+}
+
+impl StackBuffer {
+	fn new() -> Self {
+		Self { bytes: [0; BUFFER_SIZE], len: 0 }
+	}
+
+	// Write `msg` and a new line, which always fits.
+	fn write_line<M>(&mut self, msg: M)
+	where
+		M: Display,
+	{
+		// Writing to the buffer never fails.
+		let _ = write!(self, "{msg}");
+		self.len = self.len.min(BUFFER_SIZE - 1);
+		self.bytes[self.len] = b'\n';
+		self.len += 1;
+	}
+
+	fn as_bytes(&self) -> &[u8] {
+		&self.bytes[..self.len]
+	}
+}
+
+impl fmt::Write for StackBuffer {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		let len = s.len().min(BUFFER_SIZE - self.len);
+		self.bytes[self.len..self.len + len].copy_from_slice(&s.as_bytes()[..len]);
+		self.len += len;
+		Ok(())
+	}
+}
+
+#[cfg(unix)]
+mod raw {
+	use std::io;
+
+	pub(super) fn write_stderr(mut bytes: &[u8]) {
+		while !bytes.is_empty() {
+			// SAFETY: `bytes` is valid for reads of its length.
+			let written = unsafe { libc::write(libc::STDERR_FILENO, bytes.as_ptr().cast(), bytes.len()) };
+			match usize::try_from(written) {
+				Ok(written) => bytes = &bytes[written..],
+				// Reading `errno` doesn't allocate.
+				Err(_) if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted => continue,
+				// There is nothing left to report a failed write to.
+				Err(_) => return,
+			}
+		}
+	}
+
+	pub(super) fn exit(code: i32) -> ! {
+		// SAFETY: `_exit` is async-signal-safe and skips `atexit` handlers.
+		unsafe { libc::_exit(code) }
+	}
+}
+
+#[cfg(not(unix))]
+mod raw {
+	use std::io::{self, Write};
+
+	// There is no `write(2)` here, so stderr is written without
+	// buffering, which doesn't take the lock `eprint!` takes.
+	pub(super) fn write_stderr(bytes: &[u8]) {
+		let _ = io::stderr().write_all(bytes);
+	}
+
+	pub(super) fn exit(code: i32) -> ! {
+		std::process::exit(code)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::config::DEFAULT_CONFIG;
+	use crate::output::format_report;
+	use crate::report::ErrorReport;
+
+	#[test]
+	fn buffer_matches_the_normal_output() {
+		let mut buffer = StackBuffer::new();
+		buffer.write_line("Interrupted while writing the archive");
+		let report = ErrorReport::with_message("Interrupted while writing the archive");
+		assert_eq!(
+			std::str::from_utf8(buffer.as_bytes()).unwrap(),
+			format_report(&report, &DEFAULT_CONFIG, false),
+		);
+	}
+
+	#[test]
+	fn long_messages_are_cut_off() {
+		let mut buffer = StackBuffer::new();
+		buffer.write_line("x".repeat(2 * BUFFER_SIZE));
+		assert_eq!(buffer.as_bytes().len(), BUFFER_SIZE);
+		assert_eq!(buffer.as_bytes().last(), Some(&b'\n'));
+	}
+}
@@ -0,0 +1,171 @@
+//! Source snippets which point at the place in a file
+//! an error is about.
+
+use crate::hint::{Hint, HintedError};
+use crate::report::ErrorReport;
+use std::path::{Path, PathBuf};
+
+/// Display the line of a file an error is about, so users see
+/// exactly where to fix it, e.g. for a malformed configuration file.
+pub trait Snippet {
+	/// Consumes and returns `self` pointing at the 1-based `line`
+	/// and `column` of the file at `path`.
+	///
+	/// The line is displayed below the causes of the error, with a
+	/// caret under the column. If the file can't be read, only
+	/// the path, line and column are displayed.
+	/// # Example
+	/// ```rust
+	/// use giveup::{Giveup, Snippet};
+	/// let port: u16 = "80".parse()
+	///     .hint("Set the port to a number")
+	///     .snippet("Cargo.toml", 1, 2)
+	///     .giveup("Invalid configuration");
+	/// ```
+	fn snippet<P>(self, path: P, line: usize, column: usize) -> Self
+	where
+		P: Into<PathBuf>;
+
+	/// Consumes and returns `self` pointing into the file at `path`,
+	/// at the line and column mentioned by the error, e.g. by
+	/// `expected value at line 3 column 8`.
+	///
+	/// Nothing is displayed if the error doesn't mention a line,
+	/// like the parse errors of `serde_json`, `toml` and `serde_yaml` do.
+	fn source_file<P>(self, path: P) -> Self
+	where
+		P: Into<PathBuf>;
+}
+
+impl<T, E> Snippet for Result<T, HintedError<E>> {
+	fn snippet<P>(mut self, path: P, line: usize, column: usize) -> Self
+	where
+		P: Into<PathBuf>,
+	{
+		if let Err(ref mut e) = self {
+			e.hint.snippet = Some(SourceLocation::at(path.into(), line, column));
+		}
+		self
+	}
+
+	fn source_file<P>(mut self, path: P) -> Self
+	where
+		P: Into<PathBuf>,
+	{
+		if let Err(ref mut e) = self {
+			e.hint.snippet = Some(SourceLocation::in_file(path.into()));
+		}
+		self
+	}
+}
+
+impl Snippet for Hint {
+	fn snippet<P>(mut self, path: P, line: usize, column: usize) -> Self
+	where
+		P: Into<PathBuf>,
+	{
+		self.snippet = Some(SourceLocation::at(path.into(), line, column));
+		self
+	}
+
+	fn source_file<P>(mut self, path: P) -> Self
+	where
+		P: Into<PathBuf>,
+	{
+		self.snippet = Some(SourceLocation::in_file(path.into()));
+		self
+	}
+}
+
+// File an error is about and the line and column in it,
+// unless they are taken from the error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SourceLocation {
+	path: PathBuf,
+	position: Option<(usize, usize)>,
+}
+
+impl SourceLocation {
+	fn at(path: PathBuf, line: usize, column: usize) -> Self {
+		Self {
+			path,
+			position: Some((line, column)),
+		}
+	}
+
+	fn in_file(path: PathBuf) -> Self {
+		Self {
+			path,
+			position: None,
+		}
+	}
+
+	// The snippet of the file for the error in `report`.
+	pub(crate) fn render(&self, report: &ErrorReport) -> Option<String> {
+		let (line, column) = self.position.or_else(|| {
+			std::iter::once(&report.primary)
+				.chain(&report.causes)
+				.find_map(|msg| message_position(msg))
+		})?;
+		let source = std::fs::read_to_string(&self.path).ok();
+		Some(render_snippet(&self.path, source.as_deref(), line, column))
+	}
+}
+
+// Line and column in messages like `expected value at line 3 column 8`
+// or `parse error at line 3, column 8`.
+fn message_position(msg: &str) -> Option<(usize, usize)> {
+	msg.match_indices("line ").find_map(|(idx, _)| {
+		let (line, rest) = leading_number(&msg[idx + "line ".len()..])?;
+		let rest = rest.strip_prefix(',').unwrap_or(rest);
+		let (column, _) = leading_number(rest.strip_prefix(" column ")?)?;
+		Some((line, column))
+	})
+}
+
+fn leading_number(s: &str) -> Option<(usize, &str)> {
+	let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+	Some((s[..end].parse().ok()?, &s[end..]))
+}
+
+// The location followed by the line of `source` with a caret under
+// the column, laid out the same way as in compiler errors.
+pub(crate) fn render_snippet(path: &Path, source: Option<&str>, line: usize, column: usize) -> String {
+	let gutter = " ".repeat(line.to_string().len());
+	let mut snippet = format!("{gutter}--> {}:{line}:{column}\n", path.display());
+	let text = source.and_then(|source| source.lines().nth(line.checked_sub(1)?));
+	if let Some(text) = text {
+		// Tabs are kept so the caret lines up however wide they are.
+		let indent: String = text.chars()
+			.take(column.saturating_sub(1))
+			.map(|c| if c == '\t' { '\t' } else { ' ' })
+			.collect();
+		snippet.push_str(&format!("{gutter} |\n{line} | {text}\n{gutter} | {indent}^\n"));
+	}
+	snippet
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn snippets_point_at_the_column() {
+		let source = "[server]\nport = \"eighty\"\n";
+		assert_eq!(render_snippet(Path::new("app.toml"), Some(source), 2, 8), concat!(
+			" --> app.toml:2:8\n",
+			"  |\n",
+			"2 | port = \"eighty\"\n",
+			"  |        ^\n",
+		));
+		assert_eq!(render_snippet(Path::new("app.toml"), Some(source), 12, 1), "  --> app.toml:12:1\n");
+		assert_eq!(render_snippet(Path::new("app.toml"), Some("\tport = x"), 1, 9), " --> app.toml:1:9\n  |\n1 | \tport = x\n  | \t       ^\n");
+	}
+
+	#[test]
+	fn positions_are_found_in_messages() {
+		assert_eq!(message_position("expected value at line 3 column 8"), Some((3, 8)));
+		assert_eq!(message_position("TOML parse error at line 1, column 12"), Some((1, 12)));
+		assert_eq!(message_position("invalid line ending"), None);
+	}
+}
@@ -0,0 +1,61 @@
+use std::borrow::Cow;
+
+/// The text `giveup` puts around the parts of an error, so it
+/// can be translated.
+///
+/// The structure of the output stays the same in any language.
+/// # Example
+/// ```rust
+/// use giveup::Strings;
+/// giveup::config()
+///     .strings(Strings {
+///         caused_by: "Verursacht durch:".into(),
+///         note: "Hinweis:".into(),
+///         see_also: "Siehe auch:".into(),
+///         error: "Fehler".into(),
+///         ..Strings::default()
+///     })
+///     .apply();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Strings {
+	/// In front of each of the error's sources. Defaults to `Caused by:`.
+	pub caused_by: Cow<'static, str>,
+	/// In front of each note. Defaults to `note:`.
+	pub note: Cow<'static, str>,
+	/// In front of each link. Defaults to `see also:`.
+	pub see_also: Cow<'static, str>,
+	/// The label in front of the message, as in `error:` and
+	/// `error[E012]:`. Defaults to `error`.
+	pub error: Cow<'static, str>,
+	/// The label in front of [warnings](crate::Giveup::warn).
+	/// Defaults to `warning`.
+	pub warning: Cow<'static, str>,
+	/// In front of the location in the source code the error was
+	/// given up on, which is displayed in verbose output. Defaults to `at`.
+	pub location: Cow<'static, str>,
+	/// Headline of the error's `Debug` representation. Defaults to `Debug:`.
+	pub debug: Cow<'static, str>,
+	/// Headline of the span trace. Defaults to `Span trace:`.
+	pub span_trace: Cow<'static, str>,
+	/// Headline of the backtrace. Defaults to `Backtrace:`.
+	pub backtrace: Cow<'static, str>,
+}
+
+pub(crate) const DEFAULT_STRINGS: Strings = Strings {
+	caused_by: Cow::Borrowed("Caused by:"),
+	note: Cow::Borrowed("note:"),
+	see_also: Cow::Borrowed("see also:"),
+	error: Cow::Borrowed("error"),
+	warning: Cow::Borrowed("warning"),
+	location: Cow::Borrowed("at"),
+	debug: Cow::Borrowed("Debug:"),
+	span_trace: Cow::Borrowed("Span trace:"),
+	backtrace: Cow::Borrowed("Backtrace:"),
+};
+
+impl Default for Strings {
+	fn default() -> Self {
+		DEFAULT_STRINGS
+	}
+}
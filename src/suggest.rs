@@ -0,0 +1,164 @@
+use crate::hint::{Hint, HintedError};
+
+/// The candidate which is most similar to `input`, if any of them
+/// is similar enough to be what the user meant.
+///
+/// Candidates are compared ignoring case, by edit distance or with the
+/// `strsim` feature by Jaro-Winkler similarity. Use it for unknown
+/// subcommands, profile names, keys, etc.
+/// # Example
+/// ```rust
+/// let commands = ["build", "check", "config"];
+/// assert_eq!(giveup::suggest("confgi", commands), Some("config"));
+/// assert_eq!(giveup::suggest("xyz", commands), None);
+/// ```
+pub fn suggest<I, S>(input: &str, candidates: I) -> Option<S>
+where
+	I: IntoIterator<Item = S>,
+	S: AsRef<str>,
+{
+	let input = input.to_lowercase();
+	candidates.into_iter()
+		.filter_map(|candidate| {
+			let score = similarity(&input, &candidate.as_ref().to_lowercase())?;
+			Some((score, candidate))
+		})
+		// The first of equally similar candidates is picked.
+		.fold(None, |best: Option<(f64, S)>, (score, candidate)| match best {
+			Some((best_score, _)) if best_score >= score => best,
+			_ => Some((score, candidate)),
+		})
+		.map(|(_, candidate)| candidate)
+}
+
+// How similar the strings are, or `None` if they
+// are too different to be confused.
+#[cfg(not(feature = "strsim"))]
+fn similarity(input: &str, candidate: &str) -> Option<f64> {
+	// Allow about one typo per three characters.
+	let max_distance = (input.chars().count() / 3).max(1);
+	let distance = edit_distance(input, candidate);
+	(distance <= max_distance).then(|| -(distance as f64))
+}
+
+#[cfg(feature = "strsim")]
+fn similarity(input: &str, candidate: &str) -> Option<f64> {
+	let similarity = strsim::jaro_winkler(input, candidate);
+	(similarity >= 0.8).then_some(similarity)
+}
+
+// Number of inserted, removed, replaced or swapped adjacent
+// characters needed to turn `a` into `b`.
+#[cfg(any(test, not(feature = "strsim")))]
+fn edit_distance(a: &str, b: &str) -> usize {
+	let a: Vec<char> = a.chars().collect();
+	let b: Vec<char> = b.chars().collect();
+	// `distances[i][j]` is the distance between the first `i`
+	// characters of `a` and the first `j` characters of `b`.
+	let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+	for (i, row) in distances.iter_mut().enumerate() {
+		row[0] = i;
+	}
+	distances[0] = (0..=b.len()).collect();
+	for i in 1..=a.len() {
+		for j in 1..=b.len() {
+			let cost = usize::from(a[i - 1] != b[j - 1]);
+			let mut distance = (distances[i - 1][j - 1] + cost)
+				.min(distances[i - 1][j] + 1)
+				.min(distances[i][j - 1] + 1);
+			if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+				distance = distance.min(distances[i - 2][j - 2] + 1);
+			}
+			distances[i][j] = distance;
+		}
+	}
+	distances[a.len()][b.len()]
+}
+
+/// Suggest what the user might have meant.
+pub trait DidYouMean {
+	/// Consumes and returns `self` with a `Did you mean '<candidate>'?`
+	/// hint if one of the `candidates` is similar to `input`
+	/// (see [`suggest`]).
+	///
+	/// The suggestion is displayed before the other hints.
+	/// # Example
+	/// ```rust
+	/// use giveup::{DidYouMean, Giveup};
+	///
+	/// let profiles = ["debug", "release"];
+	/// let input = "release";
+	/// let profile = profiles.iter()
+	///     .find(|profile| **profile == input)
+	///     .hint("Pass one of the profiles in Cargo.toml")
+	///     .did_you_mean(input, profiles)
+	///     .giveup("Unknown profile");
+	/// ```
+	fn did_you_mean<I, S>(self, input: &str, candidates: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<str>;
+}
+
+impl<T, E> DidYouMean for Result<T, HintedError<E>> {
+	fn did_you_mean<I, S>(mut self, input: &str, candidates: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<str>,
+	{
+		if let Err(ref mut e) = self {
+			e.hint.suggestion = suggestion(input, candidates);
+		}
+		self
+	}
+}
+
+impl DidYouMean for Hint {
+	fn did_you_mean<I, S>(mut self, input: &str, candidates: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<str>,
+	{
+		self.suggestion = suggestion(input, candidates);
+		self
+	}
+}
+
+fn suggestion<I, S>(input: &str, candidates: I) -> Option<String>
+where
+	I: IntoIterator<Item = S>,
+	S: AsRef<str>,
+{
+	suggest(input, candidates).map(|candidate| format!("Did you mean '{}'?", candidate.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::giveup::{Giveup, GiveupFormatError};
+
+	#[test]
+	fn edit_distances_are_correct() {
+		assert_eq!(edit_distance("kitten", "sitting"), 3);
+		assert_eq!(edit_distance("", "abc"), 3);
+		assert_eq!(edit_distance("config", "config"), 0);
+		assert_eq!(edit_distance("confgi", "config"), 1);
+	}
+
+	#[test]
+	fn the_most_similar_candidate_is_suggested() {
+		let candidates = ["build", "bench", "check"];
+		assert_eq!(suggest("biuld", candidates), Some("build"));
+		assert_eq!(suggest("CHECK", candidates), Some("check"));
+		assert_eq!(suggest("install", candidates), None);
+		assert_eq!(suggest("x", Vec::<String>::new()), None);
+	}
+
+	#[test]
+	fn suggestions_come_before_other_hints() {
+		let result = "x".parse::<u8>();
+		let err = result.hint("Pass a number").did_you_mean("sevne", ["seven"]).unwrap_err();
+		let report = err.segments(&Default::default());
+		assert_eq!(report.hints, vec!["Did you mean 'seven'?", "Pass a number"]);
+	}
+}
@@ -0,0 +1,129 @@
+//! Mirroring reports to systemd-journald or syslog, so failures of
+//! services can be found with `journalctl` and alike.
+
+use crate::config::Config;
+use crate::output::format_report;
+use crate::report::{ErrorReport, Severity};
+use std::os::unix::net::UnixDatagram;
+
+// Socket of journald's native protocol.
+const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+
+// Socket of the local syslog daemon.
+const SYSLOG_SOCKET: &str = "/dev/log";
+
+// Send the uncolored report to journald, or to syslog if
+// journald isn't running.
+pub(crate) fn write_report(report: &ErrorReport, config: &Config) {
+	let output = format_report(report, config, false);
+	let identifier = config.program_name();
+	let Ok(socket) = UnixDatagram::unbound() else {
+		return;
+	};
+	let entry = journal_entry(report, output.trim_end(), &identifier);
+	if socket.send_to(&entry, JOURNAL_SOCKET).is_ok() {
+		return;
+	}
+	// Syslog messages are single lines.
+	for line in output.lines().filter(|line| !line.is_empty()) {
+		// There is nowhere left to report a failed write to.
+		let _ = socket.send_to(syslog_message(report, line, &identifier).as_bytes(), SYSLOG_SOCKET);
+	}
+}
+
+// Whether the process runs as a systemd service,
+// which sets these variables for its processes.
+pub(crate) fn runs_as_service() -> bool {
+	std::env::var_os("INVOCATION_ID").is_some() || std::env::var_os("JOURNAL_STREAM").is_some()
+}
+
+// Syslog priority, `err` or `warning`.
+fn priority(report: &ErrorReport) -> u8 {
+	match report.severity {
+		Severity::Warning => 4,
+		Severity::Error | Severity::Fatal => 3,
+	}
+}
+
+// Entry in journald's native format with the parts of the
+// report as separate fields.
+fn journal_entry(report: &ErrorReport, message: &str, identifier: &str) -> Vec<u8> {
+	let mut entry = Vec::new();
+	push_field(&mut entry, "MESSAGE", message);
+	push_field(&mut entry, "PRIORITY", &priority(report).to_string());
+	push_field(&mut entry, "SYSLOG_IDENTIFIER", identifier);
+	push_field(&mut entry, "ERROR", &report.primary);
+	if let Some(code) = &report.code {
+		push_field(&mut entry, "ERROR_CODE", code);
+	}
+	// Fields can be repeated, which `journalctl` shows
+	// as separate lines.
+	for cause in &report.causes {
+		push_field(&mut entry, "CAUSE", cause);
+	}
+	for hint in &report.hints {
+		push_field(&mut entry, "HINT", hint);
+	}
+	for example in &report.examples {
+		push_field(&mut entry, "EXAMPLE", &example.command);
+	}
+	entry
+}
+
+// `NAME=value`, or the name, the value's length and the value
+// if the value spans multiple lines.
+fn push_field(entry: &mut Vec<u8>, name: &str, value: &str) {
+	entry.extend_from_slice(name.as_bytes());
+	if value.contains('\n') {
+		entry.push(b'\n');
+		entry.extend_from_slice(&(value.len() as u64).to_le_bytes());
+	} else {
+		entry.push(b'=');
+	}
+	entry.extend_from_slice(value.as_bytes());
+	entry.push(b'\n');
+}
+
+// `<PRI>identifier[pid]: line` with the facility `user`.
+fn syslog_message(report: &ErrorReport, line: &str, identifier: &str) -> String {
+	let pri = 8 + priority(report);
+	format!("<{pri}>{identifier}[{}]: {line}", std::process::id())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::report::ExampleCommand;
+
+	#[test]
+	fn journal_entries_have_structured_fields() {
+		let report = ErrorReport {
+			primary: "error".to_owned(),
+			hints: vec!["hint".to_owned()],
+			examples: vec![ExampleCommand::new("touch config.toml")],
+			..ErrorReport::with_message("message")
+		};
+		let entry = journal_entry(&report, "message: error\nhint", "app");
+		let mut expected = b"MESSAGE\n".to_vec();
+		expected.extend_from_slice(&19u64.to_le_bytes());
+		expected.extend_from_slice(concat!(
+			"message: error\nhint\n",
+			"PRIORITY=3\n",
+			"SYSLOG_IDENTIFIER=app\n",
+			"ERROR=error\n",
+			"HINT=hint\n",
+			"EXAMPLE=touch config.toml\n",
+		).as_bytes());
+		assert_eq!(entry, expected);
+	}
+
+	#[test]
+	fn warnings_have_a_lower_priority() {
+		let report = ErrorReport {
+			severity: Severity::Warning,
+			..ErrorReport::with_message("message")
+		};
+		let message = syslog_message(&report, "warning: message", "app");
+		assert_eq!(message, format!("<12>app[{}]: warning: message", std::process::id()));
+	}
+}
@@ -0,0 +1,104 @@
+//! Giving up on tokio tasks which panicked or were cancelled.
+
+use crate::giveup::{GiveupExitCode, GiveupFormatError};
+use crate::options::FormatOptions;
+use crate::panic::payload_message;
+use crate::report::ErrorReport;
+use tokio::task::{Id, JoinError};
+
+/// A `tokio::task::JoinError` which tells cancelled tasks
+/// apart from those which panicked.
+///
+/// The message of a panic is displayed as the cause of the error.
+/// # Example
+/// ```rust
+/// use giveup::{Giveup, TaskError};
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// let sum = runtime.block_on(async {
+///     tokio::spawn(async { 1 + 1 }).await
+/// })
+///     .map_err(TaskError::from)
+///     .giveup("The worker failed");
+/// ```
+#[derive(Debug)]
+pub struct TaskError {
+	id: Id,
+	panic: Option<String>,
+}
+
+impl TaskError {
+	/// The ID of the task which failed.
+	pub fn id(&self) -> Id {
+		self.id
+	}
+
+	/// Whether the task was cancelled rather than panicked.
+	pub fn is_cancelled(&self) -> bool {
+		self.panic.is_none()
+	}
+
+	/// The message the task panicked with.
+	pub fn panic_message(&self) -> Option<&str> {
+		self.panic.as_deref()
+	}
+}
+
+impl From<JoinError> for TaskError {
+	fn from(e: JoinError) -> Self {
+		let id = e.id();
+		// The payload is taken out of the error, because it can't be
+		// sent to the thread giving up otherwise.
+		let panic = e.try_into_panic().ok().map(|payload| payload_message(&*payload).to_owned());
+		Self { id, panic }
+	}
+}
+
+impl GiveupFormatError for TaskError {
+	fn segments(&self, _opts: &FormatOptions) -> ErrorReport {
+		match &self.panic {
+			Some(panic) => ErrorReport {
+				primary: format!("task {} panicked", self.id),
+				causes: vec![panic.clone()],
+				..ErrorReport::default()
+			},
+			None => ErrorReport {
+				primary: format!("task {} was cancelled", self.id),
+				..ErrorReport::default()
+			},
+		}
+	}
+}
+
+impl GiveupExitCode for TaskError {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tokio::runtime::{Builder, Runtime};
+
+	fn runtime() -> Runtime {
+		Builder::new_current_thread().build().unwrap()
+	}
+
+	#[test]
+	fn panic_messages_are_the_cause() {
+		let err = runtime().block_on(async {
+			tokio::spawn(async { panic!("worker exploded") }).await.unwrap_err()
+		});
+		let err = TaskError::from(err);
+		assert!(!err.is_cancelled());
+		assert_eq!(err.format_err_msg(), format!("task {} panicked\nCaused by: worker exploded\n", err.id()));
+	}
+
+	#[test]
+	fn cancelled_tasks_are_told_apart() {
+		let err = runtime().block_on(async {
+			let handle = tokio::spawn(std::future::pending::<()>());
+			handle.abort();
+			handle.await.unwrap_err()
+		});
+		let err = TaskError::from(err);
+		assert!(err.is_cancelled());
+		assert_eq!(err.format_err_msg(), format!("task {} was cancelled\n", err.id()));
+	}
+}
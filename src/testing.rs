@@ -0,0 +1,177 @@
+//! Helpers to test code paths which give up.
+//!
+//! Only available with the `testing` feature.
+//!
+//! While [`capture`] runs, giving up doesn't print anything or exit the
+//! process. Instead the report and exit code are recorded and control
+//! returns to `capture`. This makes it possible to drive a whole CLI
+//! flow (e.g. `main`) from an integration test and inspect what would
+//! have been printed.
+//!
+//! Code which gives up outside of `capture` (e.g. on another thread)
+//! still exits the process, unless [`panic_on_exit`] is turned on.
+
+use crate::report::ErrorReport;
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+thread_local! {
+	// Every exit while capturing.
+	static CAPTURED: RefCell<Option<Vec<Exit>>> = const { RefCell::new(None) };
+	// Every report displayed without exiting while capturing them.
+	#[cfg(test)]
+	static PRINTED: RefCell<Option<Vec<ErrorReport>>> = const { RefCell::new(None) };
+}
+
+// Panic instead of exiting outside of `capture`.
+static PANIC_ON_EXIT: AtomicBool = AtomicBool::new(false);
+
+// Everything known about an intercepted exit.
+struct Exit {
+	report: ErrorReport,
+	output: String,
+	code: i32,
+}
+
+// Payload used to unwind out of an intercepted exit.
+struct Exited;
+
+/// Run `f` and return the report and exit code of every
+/// exit which would have happened.
+///
+/// Since giving up never returns, `f` stops at the first exit.
+/// The recorded reports are never colored.
+/// # Example
+/// ```rust
+/// use giveup::giveup_ensure;
+/// use giveup::testing::capture;
+/// let captured = capture(|| {
+///     giveup_ensure(false, "Missing configuration file");
+///     unreachable!();
+/// });
+/// assert_eq!(captured, vec![("Missing configuration file\n".to_owned(), 1)]);
+/// ```
+pub fn capture<F>(f: F) -> Vec<(String, i32)>
+where
+	F: FnOnce(),
+{
+	capture_exits(f).into_iter().map(|exit| (exit.output, exit.code)).collect()
+}
+
+/// Same as [`capture`], but the structured reports are returned
+/// instead of the displayed text.
+/// # Example
+/// ```rust
+/// use giveup::Giveup;
+/// use giveup::testing::capture_reports;
+/// let captured = capture_reports(|| {
+///     None::<u8>.hint("Pass a port").giveup("Missing port");
+/// });
+/// let (report, code) = &captured[0];
+/// assert_eq!(report.message, "Missing port");
+/// assert_eq!(report.hints, vec!["Pass a port"]);
+/// assert_eq!(*code, 1);
+/// ```
+pub fn capture_reports<F>(f: F) -> Vec<(ErrorReport, i32)>
+where
+	F: FnOnce(),
+{
+	capture_exits(f).into_iter().map(|exit| (exit.report, exit.code)).collect()
+}
+
+/// Panic with the displayed text instead of exiting the process
+/// when giving up outside of [`capture`].
+///
+/// This is useful for tests which give up on threads other than
+/// the test's own, where `capture` can't intercept the exit.
+pub fn panic_on_exit(enabled: bool) {
+	PANIC_ON_EXIT.store(enabled, Ordering::Relaxed);
+}
+
+fn capture_exits<F>(f: F) -> Vec<Exit>
+where
+	F: FnOnce(),
+{
+	let outer = CAPTURED.with(|captured| captured.borrow_mut().replace(Vec::new()));
+	let result = panic::catch_unwind(AssertUnwindSafe(f));
+	let captured = CAPTURED.with(|captured| {
+		std::mem::replace(&mut *captured.borrow_mut(), outer)
+	}).unwrap_or_default();
+
+	match result {
+		Err(payload) if !payload.is::<Exited>() => panic::resume_unwind(payload),
+		_ => captured,
+	}
+}
+
+/// Record the report and unwind to [`capture`] instead of exiting.
+/// Returns without doing anything if nothing is captured and
+/// [`panic_on_exit`] is off.
+pub(crate) fn intercept(report: &ErrorReport, output: &str, code: i32) {
+	let capturing = CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+		Some(captured) => {
+			captured.push(Exit { report: report.clone(), output: output.to_owned(), code });
+			true
+		},
+		None => false,
+	});
+
+	if capturing {
+		// `resume_unwind` doesn't invoke the panic hook.
+		panic::resume_unwind(Box::new(Exited));
+	} else if PANIC_ON_EXIT.load(Ordering::Relaxed) {
+		panic!("gave up with exit code {code}: {output}");
+	}
+}
+
+// Run `f` and return the reports it displayed without exiting,
+// which aren't written to stderr in the meantime.
+#[cfg(test)]
+pub(crate) fn capture_printed<F>(f: F) -> Vec<ErrorReport>
+where
+	F: FnOnce(),
+{
+	let outer = PRINTED.with(|printed| printed.borrow_mut().replace(Vec::new()));
+	f();
+	PRINTED.with(|printed| std::mem::replace(&mut *printed.borrow_mut(), outer)).unwrap_or_default()
+}
+
+// Record a report displayed without exiting. Returns whether
+// it was recorded instead of being displayed.
+#[cfg(test)]
+pub(crate) fn record_printed(report: &ErrorReport) -> bool {
+	PRINTED.with(|printed| match printed.borrow_mut().as_mut() {
+		Some(printed) => {
+			printed.push(report.clone());
+			true
+		},
+		None => false,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::giveup_ensure;
+	use std::thread;
+
+	#[test]
+	fn reports_are_captured() {
+		let captured = capture_reports(|| giveup_ensure(false, "message"));
+		assert_eq!(captured.len(), 1);
+		assert_eq!(captured[0].0.message, "message");
+		assert_eq!(captured[0].1, 1);
+	}
+
+	#[test]
+	fn exits_on_other_threads_can_panic() {
+		panic_on_exit(true);
+		let result = thread::spawn(|| giveup_ensure(false, "message")).join();
+		panic_on_exit(false);
+
+		let payload = result.unwrap_err();
+		let msg = payload.downcast_ref::<String>().unwrap();
+		assert_eq!(msg, "gave up with exit code 1: message\n");
+	}
+}
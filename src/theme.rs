@@ -0,0 +1,244 @@
+use std::fmt;
+
+/// Colors of the terminal's palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+	Black,
+	Red,
+	Green,
+	Yellow,
+	Blue,
+	Magenta,
+	Cyan,
+	White,
+	BrightBlack,
+	BrightRed,
+	BrightGreen,
+	BrightYellow,
+	BrightBlue,
+	BrightMagenta,
+	BrightCyan,
+	BrightWhite,
+}
+
+impl Color {
+	// SGR parameter which sets this color as the foreground color.
+	const fn sgr(self) -> u8 {
+		match self {
+			Color::Black => 30,
+			Color::Red => 31,
+			Color::Green => 32,
+			Color::Yellow => 33,
+			Color::Blue => 34,
+			Color::Magenta => 35,
+			Color::Cyan => 36,
+			Color::White => 37,
+			Color::BrightBlack => 90,
+			Color::BrightRed => 91,
+			Color::BrightGreen => 92,
+			Color::BrightYellow => 93,
+			Color::BrightBlue => 94,
+			Color::BrightMagenta => 95,
+			Color::BrightCyan => 96,
+			Color::BrightWhite => 97,
+		}
+	}
+}
+
+/// Style of a part of the output. The default style leaves
+/// the text as it is.
+/// # Example
+/// ```rust
+/// use giveup::{Color, Style};
+/// let style = Style::new().bold().fg(Color::Cyan);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+	bold: bool,
+	dimmed: bool,
+	italic: bool,
+	underline: bool,
+	fg: Option<Color>,
+}
+
+impl Style {
+	/// Create a style which leaves the text as it is.
+	pub const fn new() -> Self {
+		Self {
+			bold: false,
+			dimmed: false,
+			italic: false,
+			underline: false,
+			fg: None,
+		}
+	}
+
+	/// Make the text bold.
+	pub const fn bold(mut self) -> Self {
+		self.bold = true;
+		self
+	}
+
+	/// Make the text less intense.
+	pub const fn dimmed(mut self) -> Self {
+		self.dimmed = true;
+		self
+	}
+
+	/// Make the text italic.
+	pub const fn italic(mut self) -> Self {
+		self.italic = true;
+		self
+	}
+
+	/// Underline the text.
+	pub const fn underline(mut self) -> Self {
+		self.underline = true;
+		self
+	}
+
+	/// Set the color of the text.
+	pub const fn fg(mut self, color: Color) -> Self {
+		self.fg = Some(color);
+		self
+	}
+
+	/// Whether the style leaves the text as it is.
+	pub fn is_plain(&self) -> bool {
+		*self == Self::new()
+	}
+
+	// Write `text` in this style.
+	pub(crate) fn write<W>(&self, out: &mut W, text: &str) -> fmt::Result
+	where
+		W: fmt::Write + ?Sized,
+	{
+		if self.is_plain() {
+			return out.write_str(text);
+		}
+		let attributes = [(self.bold, 1), (self.dimmed, 2), (self.italic, 3), (self.underline, 4)];
+		let mut params: Vec<String> = attributes.iter()
+			.filter(|(set, _)| *set)
+			.map(|(_, param)| param.to_string())
+			.collect();
+		params.extend(self.fg.map(|color| color.sgr().to_string()));
+		write!(out, "\x1b[{}m{text}\x1b[0m", params.join(";"))
+	}
+
+	/// Return `text` in this style, e.g. for a custom
+	/// [`ReportFormatter`](crate::ReportFormatter).
+	pub fn paint(&self, text: &str) -> String {
+		let mut painted = String::new();
+		// Writing to a `String` never fails.
+		let _ = self.write(&mut painted, text);
+		painted
+	}
+}
+
+/// Styles of the parts of the output, used if the output is colored.
+///
+/// Start from one of the built-in themes and override single styles:
+/// ```rust
+/// use giveup::{Color, Style, Theme};
+/// giveup::config()
+///     .theme(Theme {
+///         hint: Style::new().fg(Color::Green),
+///         ..Theme::cargo()
+///     })
+///     .apply();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+	/// The `error:` label and the error's code.
+	pub label: Style,
+	/// The `warning:` label of [warnings](crate::Giveup::warn).
+	pub warning_label: Style,
+	/// The message passed to `giveup`.
+	pub message: Style,
+	/// The `Caused by:` in front of the error's sources.
+	pub cause_prefix: Style,
+	/// The hints.
+	pub hint: Style,
+	/// The example commands.
+	pub example: Style,
+	/// The notes including their `note:` prefix.
+	pub note: Style,
+	/// Display links as terminal hyperlinks (OSC 8).
+	pub hyperlinks: bool,
+}
+
+impl Theme {
+	/// No styling at all, e.g. for terminals which display
+	/// escape sequences verbatim.
+	pub const fn plain() -> Self {
+		Self {
+			label: Style::new(),
+			warning_label: Style::new(),
+			message: Style::new(),
+			cause_prefix: Style::new(),
+			hint: Style::new(),
+			example: Style::new(),
+			note: Style::new(),
+			hyperlinks: false,
+		}
+	}
+
+	/// Bold messages and dimmed notes (the default).
+	pub const fn standard() -> Self {
+		Self {
+			label: Style::new().bold().fg(Color::Red),
+			warning_label: Style::new().bold().fg(Color::Yellow),
+			message: Style::new().bold(),
+			note: Style::new().dimmed(),
+			hyperlinks: true,
+			..Self::plain()
+		}
+	}
+
+	/// Colors similar to the diagnostics of cargo and rustc.
+	pub const fn cargo() -> Self {
+		Self {
+			label: Style::new().bold().fg(Color::Red),
+			warning_label: Style::new().bold().fg(Color::Yellow),
+			message: Style::new().bold(),
+			cause_prefix: Style::new().bold().fg(Color::Blue),
+			hint: Style::new().fg(Color::Cyan),
+			example: Style::new().bold(),
+			note: Style::new(),
+			hyperlinks: true,
+		}
+	}
+
+	/// Bright colors and no dimmed text for better legibility.
+	pub const fn high_contrast() -> Self {
+		Self {
+			label: Style::new().bold().fg(Color::BrightRed),
+			warning_label: Style::new().bold().fg(Color::BrightYellow),
+			message: Style::new().bold().fg(Color::BrightWhite),
+			cause_prefix: Style::new().bold().fg(Color::BrightYellow),
+			hint: Style::new().bold().fg(Color::BrightCyan),
+			example: Style::new().bold().underline(),
+			note: Style::new().fg(Color::BrightWhite),
+			hyperlinks: true,
+		}
+	}
+}
+
+impl Default for Theme {
+	fn default() -> Self {
+		Self::standard()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn styles_are_combined() {
+		assert_eq!(Style::new().paint("text"), "text");
+		assert_eq!(Style::new().bold().paint("text"), "\x1b[1mtext\x1b[0m");
+		let style = Style::new().fg(Color::BrightCyan).underline().dimmed();
+		assert_eq!(style.paint("text"), "\x1b[2;4;96mtext\x1b[0m");
+	}
+}
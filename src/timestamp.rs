@@ -0,0 +1,45 @@
+//! RFC 3339 timestamps in front of reports, for tools which
+//! run under cron or in long pipelines.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// The current time in UTC, e.g. `2024-05-01T12:34:56Z`.
+pub(crate) fn now() -> String {
+	let secs = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+	rfc3339(secs)
+}
+
+// Format `secs` since the Unix epoch as an RFC 3339 timestamp in UTC.
+fn rfc3339(secs: u64) -> String {
+	let (days, secs) = (secs / 86_400, secs % 86_400);
+	let (year, month, day) = civil_from_days(days);
+	let (hour, minute, second) = (secs / 3600, secs % 3600 / 60, secs % 60);
+	format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z")
+}
+
+// Date of the day `days` after 1970-01-01 in the proleptic Gregorian
+// calendar, following Howard Hinnant's `civil_from_days`.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+	let z = days + 719_468;
+	let era = z / 146_097;
+	let day_of_era = z % 146_097;
+	let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let mp = (5 * day_of_year + 2) / 153;
+	let day = day_of_year - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	let year = year_of_era + era * 400 + u64::from(month <= 2);
+	(year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn timestamps_are_rfc3339_in_utc() {
+		assert_eq!(rfc3339(0), "1970-01-01T00:00:00Z");
+		assert_eq!(rfc3339(951_782_400), "2000-02-29T00:00:00Z");
+		assert_eq!(rfc3339(1_714_566_896), "2024-05-01T12:34:56Z");
+	}
+}
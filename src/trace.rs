@@ -0,0 +1,81 @@
+//! Recording reports as `tracing` events and adding the
+//! active spans to verbose reports.
+
+use crate::options::{format_options, Verbosity};
+use crate::report::{ErrorReport, Severity};
+use tracing_error::{SpanTrace, SpanTraceStatus};
+
+// Record the report as an error event, or as a warning event for
+// warnings. Its parts are kept as separate fields, so subscribers
+// can lay them out however they like.
+pub(crate) fn record_report(report: &ErrorReport) {
+	let code = report.code.as_deref();
+	let severity = report.severity.as_str();
+	let hints = report.hints.join("\n");
+	let causes = report.causes.join("\n");
+	match report.severity {
+		Severity::Warning => tracing::warn!(
+			target: "giveup",
+			severity,
+			code,
+			error = %report.primary,
+			hints,
+			causes,
+			"{}",
+			report.message,
+		),
+		Severity::Error | Severity::Fatal => tracing::error!(
+			target: "giveup",
+			severity,
+			code,
+			error = %report.primary,
+			hints,
+			causes,
+			"{}",
+			report.message,
+		),
+	}
+}
+
+// The active spans, if the output is verbose.
+pub(crate) fn verbose_span_trace() -> Option<String> {
+	if format_options().verbosity != Verbosity::Verbose {
+		return None;
+	}
+	span_trace()
+}
+
+// The active spans, if the subscriber records them.
+fn span_trace() -> Option<String> {
+	let span_trace = SpanTrace::capture();
+	match span_trace.status() {
+		SpanTraceStatus::CAPTURED => Some(span_trace.to_string()),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tracing_error::ErrorLayer;
+	use tracing_subscriber::layer::SubscriberExt;
+	use tracing_subscriber::Registry;
+
+	#[test]
+	fn active_spans_are_captured() {
+		let subscriber = Registry::default().with(ErrorLayer::default());
+		tracing::subscriber::with_default(subscriber, || {
+			assert_eq!(span_trace(), None);
+			let span = tracing::info_span!("load_config", path = "app.toml");
+			let _guard = span.enter();
+			let span_trace = span_trace().unwrap();
+			assert!(span_trace.contains("load_config"));
+			assert!(span_trace.contains("app.toml"));
+		});
+	}
+
+	#[test]
+	fn nothing_is_captured_without_error_layer() {
+		assert_eq!(span_trace(), None);
+	}
+}
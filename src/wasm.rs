@@ -0,0 +1,22 @@
+//! Output for `wasm32-unknown-unknown`, where there is no stderr
+//! to write to and `process::exit` can't end the program.
+
+use crate::config::Config;
+use crate::output::format_report;
+use crate::report::{ErrorReport, Severity};
+
+// Log the uncolored report to the browser's or runtime's console.
+pub(crate) fn write_report(report: &ErrorReport, config: &Config) {
+	let output = format_report(report, config, false);
+	let output = output.trim_end().into();
+	match report.severity {
+		Severity::Warning => web_sys::console::warn_1(&output),
+		_ => web_sys::console::error_1(&output),
+	}
+}
+
+// Stop the program. The host sees this as a trap, since
+// there is no way to hand it the exit code.
+pub(crate) fn exit(_code: i32) -> ! {
+	std::process::abort()
+}
@@ -0,0 +1,234 @@
+// Word wrapping of the human-readable output.
+
+use crate::strings::Strings;
+use std::fmt;
+
+// Indentation of continued lines which don't start with a known prefix.
+const DEFAULT_HANG: usize = 2;
+
+// Wrap every line of `text` which is wider than `width` at spaces.
+// Styling escape sequences don't count towards the width, and words
+// which are wider than `width` on their own (e.g. URLs) aren't split.
+#[cfg(test)]
+pub(crate) fn wrap_text(text: &str, width: usize, strings: &Strings) -> String {
+	use std::fmt::Write;
+
+	let mut wrapped = String::with_capacity(text.len());
+	let mut writer = WrapWriter::new(&mut wrapped, width, strings);
+	// Writing to a `String` never fails.
+	let _ = writer.write_str(text).and_then(|()| writer.finish());
+	wrapped
+}
+
+// Writer which wraps the text written to it the same way `wrap_text`
+// does, a line at a time. Call `finish` to write a last line that
+// doesn't end in a newline.
+pub(crate) struct WrapWriter<'a, W: ?Sized> {
+	out: &'a mut W,
+	width: usize,
+	strings: &'a Strings,
+	// Wrapped lines continue after these prefixes, so they line
+	// up with the text instead of the prefix.
+	prefixes: [String; 4],
+	// The line written so far.
+	line: String,
+	verbatim: bool,
+}
+
+impl<'a, W> WrapWriter<'a, W>
+where
+	W: fmt::Write + ?Sized,
+{
+	pub(crate) fn new(out: &'a mut W, width: usize, strings: &'a Strings) -> Self {
+		let prefixes = [
+			"- ".to_owned(),
+			format!("{} ", strings.caused_by),
+			format!("{} ", strings.note),
+			format!("{} ", strings.see_also),
+		];
+		Self {
+			out,
+			width,
+			strings,
+			prefixes,
+			line: String::new(),
+			verbatim: false,
+		}
+	}
+
+	pub(crate) fn finish(&mut self) -> fmt::Result {
+		let line = std::mem::take(&mut self.line);
+		self.write_line(&line, "")
+	}
+
+	fn write_line(&mut self, content: &str, newline: &str) -> fmt::Result {
+		if self.verbatim || visible_width(content) <= self.width || is_snippet_line(&strip_styling(content)) {
+			self.out.write_str(content)?;
+		} else {
+			wrap_line(self.out, content, self.width, &self.prefixes)?;
+		}
+		self.out.write_str(newline)?;
+
+		// `Debug` output and traces are laid out by themselves.
+		let plain = strip_styling(content);
+		let strings = self.strings;
+		if plain == strings.debug || plain == strings.span_trace || plain == strings.backtrace {
+			self.verbatim = true;
+		}
+		Ok(())
+	}
+}
+
+impl<W> fmt::Write for WrapWriter<'_, W>
+where
+	W: fmt::Write + ?Sized,
+{
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		let mut rest = s;
+		while let Some(idx) = rest.find('\n') {
+			self.line.push_str(&rest[..idx]);
+			let mut line = std::mem::take(&mut self.line);
+			self.write_line(&line, "\n")?;
+			// Keep the allocation for the next line.
+			line.clear();
+			self.line = line;
+			rest = &rest[idx + 1..];
+		}
+		self.line.push_str(rest);
+		Ok(())
+	}
+}
+
+fn wrap_line<W>(wrapped: &mut W, line: &str, width: usize, prefixes: &[String]) -> fmt::Result
+where
+	W: fmt::Write + ?Sized,
+{
+	let words = line.trim_start_matches(' ');
+	let indent = line.len() - words.len();
+	let plain = strip_styling(words);
+	let prefix = prefixes.iter()
+		.find(|prefix| plain.starts_with(prefix.as_str()))
+		.map_or(DEFAULT_HANG, |prefix| prefix.len());
+	// Leave at least half of the width for the text itself.
+	let hang = (indent + prefix).min(width / 2);
+
+	wrapped.write_str(&line[..indent])?;
+	let mut line_width = indent;
+	let mut line_start = true;
+	for word in words.split(' ') {
+		let word_width = visible_width(word);
+		if !line_start && line_width + 1 + word_width > width {
+			write!(wrapped, "\n{:hang$}", "")?;
+			line_width = hang;
+			line_start = true;
+		}
+		if !line_start {
+			wrapped.write_char(' ')?;
+			line_width += 1;
+		}
+		wrapped.write_str(word)?;
+		line_width += word_width;
+		line_start = false;
+	}
+	Ok(())
+}
+
+// Lines of source snippets like ` --> app.toml:2:8` and `2 | port = 80`,
+// which are cut off rather than wrapped.
+fn is_snippet_line(line: &str) -> bool {
+	let rest = line.trim_start_matches(|c: char| c == ' ' || c.is_ascii_digit());
+	rest.starts_with("--> ") || rest.starts_with('|')
+}
+
+// Number of characters in `s` which take up space on the terminal.
+fn visible_width(s: &str) -> usize {
+	strip_styling(s).chars().count()
+}
+
+// Remove the escape sequences used for styling (`ESC [ ... m`)
+// and hyperlinks (`ESC ] ... ESC \`) from `s`.
+fn strip_styling(s: &str) -> String {
+	let mut plain = String::with_capacity(s.len());
+	let mut chars = s.chars();
+	while let Some(c) = chars.next() {
+		if c != '\x1b' {
+			plain.push(c);
+			continue;
+		}
+		match chars.next() {
+			Some('[') => {
+				// Control sequences end with a character in `@..=~`.
+				for c in chars.by_ref() {
+					if ('@'..='~').contains(&c) {
+						break;
+					}
+				}
+			},
+			Some(']') => {
+				// Operating system commands end with `ESC \` or BEL.
+				while let Some(c) = chars.next() {
+					if c == '\x07' || (c == '\x1b' && chars.next() == Some('\\')) {
+						break;
+					}
+				}
+			},
+			_ => {},
+		}
+	}
+	plain
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn short_lines_are_kept() {
+		let text = "message: error\nhint\n";
+		assert_eq!(wrap_text(text, 80, &Strings::default()), text);
+	}
+
+	#[test]
+	fn long_lines_are_wrapped_with_hanging_indentation() {
+		let text = "message: the error is long\nCaused by: the cause is long, too\n- a hint\n    `some example command`\n";
+		assert_eq!(wrap_text(text, 24, &Strings::default()), concat!(
+			"message: the error is\n  long\n",
+			"Caused by: the cause is\n           long, too\n",
+			"- a hint\n",
+			"    `some example\n      command`\n",
+		));
+	}
+
+	#[test]
+	fn styling_doesnt_count_towards_the_width() {
+		let styled = "\x1b[1mmessage\x1b[0m: error\n\x1b[2mnote: a long note\x1b[0m\n";
+		assert_eq!(wrap_text(styled, 17, &Strings::default()), styled);
+		assert_eq!(wrap_text(styled, 12, &Strings::default()), "\x1b[1mmessage\x1b[0m:\n  error\n\x1b[2mnote: a long\n      note\x1b[0m\n");
+		let link = "see also: \x1b]8;;https://example.com\x1b\\https://example.com\x1b]8;;\x1b\\";
+		assert_eq!(visible_width(link), 29);
+	}
+
+	#[test]
+	fn text_can_be_written_in_pieces() {
+		use std::fmt::Write;
+
+		let text = "message: the error is long\nCaused by: the cause is long, too\nno newline at the end";
+		let mut wrapped = String::new();
+		let strings = Strings::default();
+		let mut writer = WrapWriter::new(&mut wrapped, 24, &strings);
+		for piece in text.split_inclusive(' ') {
+			writer.write_str(piece).unwrap();
+		}
+		writer.finish().unwrap();
+		assert_eq!(wrapped, wrap_text(text, 24, &strings));
+	}
+
+	#[test]
+	fn long_words_and_backtraces_are_not_split() {
+		let text = "message: https://example.com/a/very/long/url\nBacktrace:\n   0: some::very::long::function::name\n";
+		assert_eq!(wrap_text(text, 20, &Strings::default()), concat!(
+			"message:\n  https://example.com/a/very/long/url\n",
+			"Backtrace:\n   0: some::very::long::function::name\n",
+		));
+	}
+}
@@ -0,0 +1,48 @@
+// Turns on verbose output, which is global to the
+// process, so it runs in a process of its own.
+#![cfg(feature = "testing")]
+
+use giveup::testing::capture_reports;
+use giveup::{retry_or_giveup, GiveupAll, GiveupLock, Verbosity};
+use std::panic;
+use std::sync::Mutex;
+
+// Where the reports of `f` say they were given up on.
+fn locations<F>(f: F) -> Vec<Option<String>>
+where
+	F: FnOnce(),
+{
+	capture_reports(f).into_iter().map(|(report, _)| report.location).collect()
+}
+
+fn at(line: u32) -> Vec<Option<String>> {
+	vec![Some(format!("{}:{line}", file!()))]
+}
+
+#[test]
+fn reports_point_at_the_caller() {
+	giveup::set_verbosity(Verbosity::Verbose);
+
+	let line = line!() + 2;
+	let captured = locations(|| {
+		["x"].iter().map(|s| s.parse::<u8>()).giveup_all("Invalid numbers");
+	});
+	assert_eq!(captured, at(line));
+
+	let mutex = Mutex::new(42);
+	let _ = panic::catch_unwind(|| {
+		let _guard = mutex.lock().unwrap();
+		panic!("poisoning the mutex");
+	});
+	let line = line!() + 2;
+	let captured = locations(|| {
+		let _guard = mutex.lock().giveup_lock("Failed to read the configuration");
+	});
+	assert_eq!(captured, at(line));
+
+	let line = line!() + 2;
+	let captured = locations(|| {
+		retry_or_giveup(2, || "x".parse::<u8>(), "Failed to get the answer");
+	});
+	assert_eq!(captured, at(line));
+}
@@ -0,0 +1,38 @@
+// The derived code refers to `::giveup`, which only
+// resolves outside of the crate itself.
+#![cfg(feature = "derive")]
+
+use giveup::{Example, Hint, Hintable};
+
+#[derive(Hintable)]
+#[allow(dead_code)]
+enum ConfigError {
+	/// Documentation is ignored.
+	#[hint("Create a configuration file")]
+	#[example("touch config-path")]
+	Missing,
+	#[hint("Fix the syntax error")]
+	Invalid { line: usize },
+	#[example("myapp --config config-path", "myapp -c config-path")]
+	Unknown(String),
+	Other,
+}
+
+#[derive(Hintable)]
+#[hint(r"Check the network connection")]
+pub(crate) struct NetworkError;
+
+#[test]
+fn hints_are_derived_for_variants() {
+	let expected = Hint::new("Create a configuration file").example("touch config-path");
+	assert_eq!(ConfigError::Missing.hint(), Some(expected));
+	assert_eq!(ConfigError::Invalid { line: 1 }.hint(), Some(Hint::new("Fix the syntax error")));
+	let expected = Hint::default().examples(["myapp --config config-path", "myapp -c config-path"]);
+	assert_eq!(ConfigError::Unknown(String::new()).hint(), Some(expected));
+	assert_eq!(ConfigError::Other.hint(), None);
+}
+
+#[test]
+fn hints_are_derived_for_structs() {
+	assert_eq!(NetworkError.hint(), Some(Hint::new("Check the network connection")));
+}
@@ -0,0 +1,28 @@
+// `main` returning an `Exit` with `?` on `anyhow::Result`s.
+#![cfg(all(feature = "anyhow", feature = "testing"))]
+
+use anyhow::Context;
+use giveup::testing::capture;
+use giveup::{ErrorAdapter, Exit};
+use std::process::Termination;
+
+fn read_config() -> anyhow::Result<String> {
+	std::fs::read_to_string("missing.toml").context("Failed to read the configuration")
+}
+
+fn main_with_anyhow() -> Exit<ErrorAdapter<anyhow::Error>> {
+	Exit::run(|| {
+		read_config()?;
+		Ok(())
+	})
+}
+
+#[test]
+fn anyhow_errors_can_be_returned_with_question_marks() {
+	let captured = capture(|| {
+		let _ = main_with_anyhow().report();
+	});
+	let (output, code) = &captured[0];
+	assert!(output.starts_with("Failed to read the configuration\nCaused by: No such file or directory"));
+	assert_eq!(*code, 1);
+}
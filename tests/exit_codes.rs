@@ -0,0 +1,21 @@
+// Installs an exit code map, which is global to
+// the process, so it runs in a process of its own.
+#![cfg(feature = "testing")]
+
+use giveup::sysexits::{EX_DATAERR, EX_NOINPUT};
+use giveup::testing::capture;
+use giveup::{ExitCodeMap, Giveup};
+use std::io::{self, ErrorKind};
+
+#[test]
+fn errors_given_up_on_are_mapped() {
+	giveup::config().exit_code_map(ExitCodeMap::sysexits()).apply();
+	let captured = capture(|| {
+		"x".parse::<u8>().giveup("Invalid port");
+	});
+	assert_eq!(captured[0].1, EX_DATAERR);
+	let captured = capture(|| {
+		Err::<(), _>(io::Error::new(ErrorKind::NotFound, "missing")).giveup("Failed to read the configuration");
+	});
+	assert_eq!(captured[0].1, EX_NOINPUT);
+}
@@ -0,0 +1,37 @@
+// `main` returning a `HintedError`, which is only checked from
+// the outside of the crate like it's used.
+#![cfg(feature = "testing")]
+
+use giveup::testing::capture;
+use giveup::{Example, Giveup, HintedError};
+use std::process::Termination;
+
+fn run() -> Result<(), HintedError<std::num::ParseIntError>> {
+	"x".parse::<u8>()
+		.hint("Pass a number")
+		.example("app --port 80")?;
+	Ok(())
+}
+
+// Backtraces depend on `RUST_BACKTRACE`.
+fn without_backtraces() {
+	giveup::config().backtrace(false).apply();
+}
+
+#[test]
+fn results_returned_from_main_display_the_report() {
+	without_backtraces();
+	// `main` prints the error the same way.
+	let printed = format!("Error: {:?}", run().unwrap_err());
+	assert_eq!(printed, "Error: invalid digit found in string\nPass a number: `app --port 80`");
+	assert!(format!("{:#?}", run().unwrap_err()).starts_with("HintedError {"));
+}
+
+#[test]
+fn errors_returned_from_main_are_reported_like_giving_up() {
+	without_backtraces();
+	let captured = capture(|| {
+		let _ = run().unwrap_err().report();
+	});
+	assert_eq!(captured, vec![("invalid digit found in string\nPass a number: `app --port 80`\n".to_owned(), 1)]);
+}